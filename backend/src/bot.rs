@@ -0,0 +1,258 @@
+//! Bot players the PLAY_CHAIN can seat in empty slots.
+//!
+//! Strategies only read `MatchData` and return an action; the contract applies
+//! it through the same handlers as a human move, so bot moves are validated,
+//! logged and replayed like any other move.
+
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+use crate::game::WHOT_VALUE;
+use crate::{Card, CardSuit, MatchData};
+
+/// Suits a Whot card can demand
+pub const DEMANDABLE_SUITS: [CardSuit; 5] = [
+    CardSuit::Circle,
+    CardSuit::Triangle,
+    CardSuit::Cross,
+    CardSuit::Square,
+    CardSuit::Star,
+];
+
+/// Bot strength, weakest first
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum BotDifficulty {
+    /// Any legal card, picked at random
+    Random,
+    /// Attacks with special cards first, saves Whot cards for last
+    Greedy,
+    /// Tracks which suits are still unseen and steers play towards its strong suits
+    SuitCounting,
+}
+
+/// What a bot wants to do on its turn
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BotAction {
+    Play { card_id: u8, chosen_suit: Option<CardSuit> },
+    Draw,
+}
+
+/// Pick the action for the bot in `seat`; `seed` drives the Random strategy
+pub fn choose_action(match_data: &MatchData, seat: usize, difficulty: BotDifficulty, seed: u64) -> BotAction {
+    let Some(player) = match_data.players.get(seat).and_then(|p| p.as_ref()) else {
+        return BotAction::Draw;
+    };
+    let hand = &player.hand;
+
+    let playable: Vec<usize> = match_data.legal_moves(seat).playable.iter()
+        .map(|playable| playable.index)
+        .collect();
+    if playable.is_empty() {
+        return BotAction::Draw;
+    }
+
+    let card_index = match difficulty {
+        BotDifficulty::Random => playable[(mix(seed) % playable.len() as u64) as usize],
+        BotDifficulty::Greedy => best_by(&playable, |index| greedy_score(match_data, &hand[index])),
+        BotDifficulty::SuitCounting => {
+            let unseen = unseen_by_suit(match_data, hand);
+            best_by(&playable, |index| counting_score(match_data, seat, hand, index, &unseen))
+        }
+    };
+
+    let chosen_suit = (hand[card_index].value == WHOT_VALUE).then(|| match difficulty {
+        BotDifficulty::Random => DEMANDABLE_SUITS[(mix(seed.rotate_left(17)) % 5) as usize],
+        BotDifficulty::Greedy => strongest_suit(hand, card_index, &[0; 5]),
+        BotDifficulty::SuitCounting => strongest_suit(hand, card_index, &unseen_by_suit(match_data, hand)),
+    });
+
+    BotAction::Play { card_id: hand[card_index].id, chosen_suit }
+}
+
+// ================================================================================
+// STRATEGY HELPERS
+// ================================================================================
+
+/// Greedy: answer penalties, then attack, then dump high cards, Whot last
+fn greedy_score(match_data: &MatchData, card: &Card) -> i32 {
+    if match_data.blocks_penalty(card) {
+        return 100;
+    }
+    match match_data.effect_of(card, None) {
+        Some(_) => 50 + i32::from(card.value),
+        None if card.value == WHOT_VALUE => 0,
+        None => 10 + i32::from(card.value),
+    }
+}
+
+/// Suit counting: keep control of suits we hold and opponents are unlikely to have
+fn counting_score(match_data: &MatchData, seat: usize, hand: &[Card], index: usize, unseen: &[i32; 5]) -> i32 {
+    let card = &hand[index];
+    if match_data.blocks_penalty(card) {
+        return 1000;
+    }
+    if card.value == WHOT_VALUE {
+        // Only worth it when nothing else fits
+        return -100;
+    }
+
+    // Cards left in the played suit after this one, minus how many opponents may still hold
+    let suit_index = suit_slot(card.suit).unwrap_or(0);
+    let remaining_in_suit = hand.iter().enumerate()
+        .filter(|(i, c)| *i != index && c.suit == card.suit)
+        .count() as i32;
+    let mut score = remaining_in_suit * 10 - unseen[suit_index];
+
+    // Hit the next player harder when they are about to go out
+    let next = match_data.next_seat(seat);
+    let next_is_close = match_data.players[next].as_ref().is_some_and(|p| p.hand_size <= 2);
+    if match_data.effect_of(card, None).is_some() {
+        score += if next_is_close { 200 } else { 20 };
+    }
+    score
+}
+
+/// Suit to demand with a Whot: the one we hold most of, ties broken by fewest unseen
+fn strongest_suit(hand: &[Card], whot_index: usize, unseen: &[i32; 5]) -> CardSuit {
+    let mut held = [0i32; 5];
+    for (index, card) in hand.iter().enumerate() {
+        if index != whot_index {
+            if let Some(slot) = suit_slot(card.suit) {
+                held[slot] += 1;
+            }
+        }
+    }
+    let best = (0..5)
+        .max_by_key(|&slot| (held[slot], -unseen[slot], -(slot as i32)))
+        .unwrap_or(0);
+    DEMANDABLE_SUITS[best]
+}
+
+/// Cards of each suit not in our hand and not yet on the discard pile
+fn unseen_by_suit(match_data: &MatchData, hand: &[Card]) -> [i32; 5] {
+    let mut unseen = [14, 14, 14, 14, 7];
+    for card in hand.iter().chain(match_data.discard_pile.iter()) {
+        if let Some(slot) = suit_slot(card.suit) {
+            unseen[slot] -= 1;
+        }
+    }
+    unseen
+}
+
+fn suit_slot(suit: CardSuit) -> Option<usize> {
+    DEMANDABLE_SUITS.iter().position(|s| *s == suit)
+}
+
+/// Highest scoring index; the earliest wins ties so choices are deterministic
+fn best_by(indices: &[usize], score: impl Fn(usize) -> i32) -> usize {
+    let mut best = indices[0];
+    let mut best_score = score(best);
+    for &index in &indices[1..] {
+        let candidate = score(index);
+        if candidate > best_score {
+            best = index;
+            best_score = candidate;
+        }
+    }
+    best
+}
+
+/// SplitMix64 finaliser - spreads a small seed over all bits
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::seated_match;
+    use crate::{MatchStatus, PendingPenalty};
+
+    const DIFFICULTIES: [BotDifficulty; 3] = [BotDifficulty::Random, BotDifficulty::Greedy, BotDifficulty::SuitCounting];
+
+    /// Started match with a bot of each difficulty around the table
+    fn started_match(seed: u64) -> MatchData {
+        let mut match_data = seated_match(&[true; 3], seed);
+        match_data.start(0).expect("match starts");
+        match_data
+    }
+
+    #[test]
+    fn every_choice_is_a_legal_move() {
+        for seed in [3, 77, 2_024] {
+            let mut match_data = started_match(seed);
+            for step in 1..500u64 {
+                if match_data.status != MatchStatus::InProgress {
+                    break;
+                }
+                let seat = match_data.current_player_index;
+                let legal = match_data.legal_moves(seat);
+                match choose_action(&match_data, seat, DIFFICULTIES[seat], seed ^ step) {
+                    BotAction::Play { card_id, chosen_suit } => {
+                        let playable = legal.playable.iter()
+                            .find(|playable| playable.card.id == card_id)
+                            .unwrap_or_else(|| panic!("seed {} step {}: card {} is not playable", seed, step, card_id));
+                        assert_eq!(chosen_suit.is_some(), playable.requires_suit);
+                        assert!(chosen_suit.is_none_or(|suit| DEMANDABLE_SUITS.contains(&suit)));
+                        match_data.play_cards(seat, &[playable.index], chosen_suit, step).expect("legal play is accepted");
+                    }
+                    BotAction::Draw => {
+                        assert!(legal.playable.is_empty() && legal.can_draw, "seed {} step {}: drew holding a playable card", seed, step);
+                        match_data.draw_card(seat, step).expect("legal draw is accepted");
+                    }
+                }
+            }
+            assert_eq!(match_data.status, MatchStatus::Finished, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn the_same_seed_makes_the_same_choice() {
+        let match_data = started_match(11);
+        let seat = match_data.current_player_index;
+        for difficulty in DIFFICULTIES {
+            for seed in 0..20 {
+                assert_eq!(
+                    choose_action(&match_data, seat, difficulty, seed),
+                    choose_action(&match_data.clone(), seat, difficulty, seed),
+                );
+            }
+        }
+
+        // Only the Random bot's pick depends on the seed
+        let mut picks: Vec<BotAction> = (0..20).map(|seed| choose_action(&match_data, seat, BotDifficulty::Random, seed)).collect();
+        picks.dedup();
+        assert!(match_data.legal_moves(seat).playable.len() < 2 || picks.len() > 1);
+    }
+
+    #[test]
+    fn greedy_answers_a_pending_penalty() {
+        let mut match_data = started_match(5);
+        match_data.current_player_index = 0;
+        match_data.discard_pile = vec![Card::new(CardSuit::Circle, 2)];
+        match_data.whot_demand = None;
+        match_data.pending_penalty = Some(PendingPenalty {
+            source: Card::new(CardSuit::Circle, 2),
+            raised_by: 2,
+            total: 2,
+        });
+        // A bigger special card and a Whot are on offer, the Pick Two still goes first
+        let blocker = Card::new(CardSuit::Star, 2);
+        match_data.players[0].as_mut().expect("seated").hand = vec![
+            Card::new(CardSuit::Circle, 14),
+            Card::new(CardSuit::Whot, WHOT_VALUE),
+            blocker.clone(),
+            Card::new(CardSuit::Circle, 13),
+        ];
+
+        for difficulty in [BotDifficulty::Greedy, BotDifficulty::SuitCounting] {
+            assert_eq!(
+                choose_action(&match_data, 0, difficulty, 0),
+                BotAction::Play { card_id: blocker.id, chosen_suit: None },
+            );
+        }
+    }
+}
@@ -1,3 +1,5 @@
 // Chain-specific logic modules
 pub mod user_chain;
 pub mod play_chain;
+pub mod stakes;
+pub mod tournament_chain;
@@ -1,18 +1,36 @@
-use linera_sdk::linera_base_types::{AccountOwner, ChainId};
+use linera_sdk::abis::fungible::Account;
+use linera_sdk::linera_base_types::{AccountOwner, ApplicationId, ChainId};
+use linot::bot::{self, BotAction, BotDifficulty};
+use linot::chat::{self, ChatEntry, ChatError, Emote, Reaction, CHAT_LOG_CAPACITY};
+use linot::escrow::{self, Escrow, EscrowError, Payout, Stake, TokenLedger};
+use linot::game::{DrawOutcome, GameError, WHOT_VALUE};
+use linot::replay::{MatchHeader, MoveKind, MoveRecord};
 use linot::{
-    Card, CardSuit, GameEvent, LinotResponse, MatchStatus, Message, Player,
-    SpecialEffect, UserStatus, GAME_STREAM_NAME, INITIAL_HAND_SIZE, MAX_PLAYERS, MIN_PLAYERS,
+    Card, CardSuit, GameEvent, InactivityPolicy, LinotResponse, MatchData, MatchOptions,
+    MatchStatus, Message, Player, RuleSet, TimeControl, UserStatus, GAME_STREAM_NAME,
+    MAX_PLAYERS, MIN_PLAYERS,
 };
 
 use super::super::LinotContract;
 
+/// Upper bound on bot moves played in one block, so bot-only stretches stay cheap
+const MAX_BOT_MOVES_PER_TRIGGER: usize = 32;
+
+/// Upper bound on expired turns settled in one block
+const MAX_SETTLED_TURNS: usize = 64;
+
 impl LinotContract {
     // ================================================================================
     // PLAY_CHAIN OPERATIONS (Mutations executed on PLAY_CHAIN)
     // ================================================================================
 
     /// Create a new match on PLAY_CHAIN
-    pub async fn handle_create_match(&mut self, max_players: u8, nickname: String) -> LinotResponse {
+    pub async fn handle_create_match(
+        &mut self,
+        max_players: u8,
+        nickname: String,
+        options: MatchOptions,
+    ) -> LinotResponse {
         // Validate
         if max_players < MIN_PLAYERS || max_players > MAX_PLAYERS {
             return LinotResponse::Error(format!(
@@ -20,9 +38,15 @@ impl LinotContract {
                 MIN_PLAYERS, MAX_PLAYERS
             ));
         }
+        if options.clock.is_some_and(|clock| clock.initial_micros == 0) {
+            return LinotResponse::Error("Clock needs a non-zero time bank".to_string());
+        }
+        if let Err(error) = options.rules.unwrap_or_default().validate() {
+            return LinotResponse::Error(error.to_string());
+        }
 
         // Create match data
-        let match_data = self.create_match(max_players, nickname.clone());
+        let match_data = self.create_match(max_players, nickname.clone(), options);
         
         // Set state
         self.state.match_data.set(match_data);
@@ -49,7 +73,9 @@ impl LinotContract {
         creator_chain: ChainId,
         max_players: u8,
         nickname: String,
+        options: MatchOptions,
     ) -> Result<usize, String> {
+        let rules = options.rules.unwrap_or_default();
         // Validate
         if max_players < MIN_PLAYERS || max_players > MAX_PLAYERS {
             return Err(format!(
@@ -57,66 +83,26 @@ impl LinotContract {
                 MIN_PLAYERS, MAX_PLAYERS
             ));
         }
-
-        // Create match using manual initialization
-        let mut match_data = crate::MatchData {
-            players: vec![None; max_players as usize],
-            deck: Vec::new(),
-            deck_size: 0,
-            discard_pile: Vec::new(),
-            current_player_index: 0,
-            status: MatchStatus::Waiting,
-            max_players,
-            winner_index: None,
-            pending_draw_stack: 0,
-            pending_draw_type: None,
-            active_demand_suit: None,
-            turn_start_time: None,
-            turn_duration: 0,
-        };
-        
-        // Set creator as first player with their chain info
-        match_data.players[0] = Some(Player {
-            owner: creator_owner,
-            chain_id: creator_chain,
-            nickname: nickname.clone(),
-            hand: Vec::new(),
-            hand_size: 0,
-            called_last_card: false,
-        });
-
-        // Create and shuffle deck using Fisher-Yates
-        let mut deck = Vec::with_capacity(61);
-        
-        // Regular suits (Circle, Triangle, Cross, Square) - values 1 to 14
-        for suit in [CardSuit::Circle, CardSuit::Triangle, CardSuit::Cross, CardSuit::Square] {
-            for value in 1..=14 {
-                deck.push(Card { suit, value });
-            }
-        }
-        
-        // Star suit - values 1 to 7
-        for value in 1..=7 {
-            deck.push(Card { suit: CardSuit::Star, value });
+        if options.clock.is_some_and(|clock| clock.initial_micros == 0) {
+            return Err("Clock needs a non-zero time bank".to_string());
         }
-        
-        // Whot cards (wild) - 5 total
-        for _ in 0..5 {
-            deck.push(Card { suit: CardSuit::Whot, value: 20 });
+        if let Err(error) = rules.validate() {
+            return Err(error.to_string());
         }
 
-        // Fisher-Yates shuffle
+        // Shuffled lobby - the seed is kept so the match can be replayed later
         let seed = self.runtime.system_time().micros();
-        let mut state = seed;
-        for i in (1..deck.len()).rev() {
-            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
-            let j = (state as usize) % (i + 1);
-            deck.swap(i, j);
-        }
+        let time_control = options.time_control.unwrap_or_default();
+        let mut match_data = MatchData::new_lobby_with_time_control(max_players, seed, time_control);
+        match_data.match_id = self.next_match_id();
+        match_data.clock = options.clock;
+        match_data.inactivity_policy = options.inactivity_policy.unwrap_or_default();
+        match_data.rules = rules;
+        
+        // Set creator as first player with their chain info
+        match_data.players[0] = Some(Player::new(creator_chain, creator_owner, nickname.clone()));
 
-        match_data.deck = deck;
-        match_data.deck_size = 61;
-        match_data.status = MatchStatus::Waiting;
+        let new_match_id = match_data.match_id;
         
         // Set state on PLAY_CHAIN
         self.state.match_data.set(match_data);
@@ -129,67 +115,50 @@ impl LinotContract {
             max_players,
         });
 
-        log::info!("PLAY_CHAIN: Match created via cross-chain message from {:?}", creator_chain);
-        Ok(0) // Return match ID
+        log::info!("PLAY_CHAIN: Match {} ({:?}) created via cross-chain message from {:?}", new_match_id, time_control, creator_chain);
+        Ok(new_match_id as usize)
     }
 
     /// Start the match (deal cards, set first player)
-    pub async fn handle_start_match(&mut self) -> LinotResponse {
+    pub async fn handle_start_match(&mut self, player_owner: Option<AccountOwner>) -> LinotResponse {
+        let now = self.now();
         let match_data = self.state.match_data.get_mut();
 
-        // Validate enough players
-        let player_count = match_data.players.iter()
-            .filter(|p| p.is_some()).count();
-        
-        log::info!("PLAY_CHAIN: handle_start_match called. Player count: {}", player_count);
-
-        if player_count < MIN_PLAYERS as usize {
-            let msg = format!(
-                "Not enough players. Need at least {}. Current: {}",
-                MIN_PLAYERS, player_count
-            );
-            log::error!("PLAY_CHAIN: StartMatch failed - {}", msg);
-            return LinotResponse::Error(msg);
-        }
-
-        // Deal cards inline to avoid borrow conflict
-        for player in match_data.players.iter_mut().filter_map(|p| p.as_mut()) {
-            for _ in 0..INITIAL_HAND_SIZE {
-                if let Some(card) = match_data.deck.pop() {
-                    player.hand.push(card);
-                }
-            }
-            player.update_hand_size();
+        log::info!("PLAY_CHAIN: handle_start_match called. Player count: {}", match_data.player_count());
+
+        // Everyone has paid into a staked match - only the host says when it begins
+        if match_data.escrow.is_some() && !player_owner.is_some_and(|owner| match_data.is_host(&owner)) {
+            log::warn!("PLAY_CHAIN: StartMatch rejected - not the host");
+            return LinotResponse::Error(GameError::NotHost.to_string());
         }
-        
-        // Add first card to discard pile
-        if let Some(card) = match_data.deck.pop() {
-match_data.discard_pile.push(card);
+
+        // Seating is fixed from here on - keep it with the seed for replays
+        let header = MatchHeader::from_lobby(match_data);
+
+        if let Err(error) = match_data.start(now) {
+            log::error!("PLAY_CHAIN: StartMatch failed - {}", error);
+            return LinotResponse::Error(error.to_string());
         }
-        
-        match_data.deck_size = match_data.deck.len();
 
-        // Set first player
-        match_data.current_player_index = 0;
-        match_data.status = MatchStatus::InProgress;
-        
-        // Initialize turn timer
-        let start_time = self.runtime.system_time().micros();
-        match_data.turn_start_time = Some(start_time);
+        let starter_seat = player_owner
+            .and_then(|owner| match_data.seat_of(&owner))
+            .unwrap_or(0);
+        let player_count = match_data.player_count();
+        let duration_micros = match_data.turn_allotment();
 
         // Get first player and top card
-        let first_player = match_data.players[0].as_ref()
-            .map(|p| p.nickname.clone())
-            .unwrap_or_default();
+        let first_player = self.seat_nickname(0);
+        let match_data = self.state.match_data.get();
         let top_card = match_data.discard_pile.last().cloned()
-            .unwrap_or(Card { suit: CardSuit::Circle, value: 1 });
-
-        // Emit event
+            .unwrap_or(Card::new(CardSuit::Circle, 1));
         let player_names: Vec<String> = match_data.players.iter()
             .filter_map(|p| p.as_ref().map(|player| player.nickname.clone()))
             .collect();
-        
-        // Borrow ends here
+
+        let match_id = header.match_id;
+        self.state.match_headers.insert(&match_id, header)
+            .expect("Failed to store match header");
+        self.record_move(MoveRecord::new(starter_seat, MoveKind::Start, now)).await;
 
         self.emit_event(GameEvent::MatchStarted {
             players: player_names,
@@ -200,7 +169,7 @@ match_data.discard_pile.push(card);
         // Emit turn started event
         self.emit_event(GameEvent::TurnStarted {
             player_nickname: first_player,
-            duration_micros: linot::TURN_TIMEOUT_MICROS,
+            duration_micros,
         });
 
         log::info!("PLAY_CHAIN: Match started with {} players", player_count);
@@ -216,28 +185,49 @@ match_data.discard_pile.push(card);
         &mut self,
         player_owner: AccountOwner,
         player_chain: ChainId,
-        nickname: String
+        nickname: String,
+        stake: Option<Stake>,
     ) {
         let origin_chain = self.runtime.message_origin_chain_id()
             .expect("Message has no origin");
         let play_chain_id = self.runtime.chain_id();
 
         let match_data = self.state.match_data.get_mut();
+        let player = Player::new(player_chain, player_owner, nickname.clone());
+        let account = Account {
+            chain_id: player_chain,
+            owner: player_owner,
+        };
+
+        // A staked match only takes players who paid its exact fee
+        let paid = match &match_data.escrow {
+            Some(escrow) => escrow.check_payment(stake),
+            None if stake.is_some() => Err(EscrowError::StakeMismatch),
+            None => Ok(()),
+        };
+        if let Err(error) = &paid {
+            log::warn!("PLAY_CHAIN: Join rejected - {}", error);
+        }
 
         // Find empty slot
-        if let Some(index) = match_data.players.iter().position(|p| p.is_none()) {
-            let player = Player::new(player_chain, player_owner, nickname.clone());
-            match_data.players[index] = Some(player);
+        let seat = match match_data.status {
+            MatchStatus::Waiting if paid.is_ok() => match_data.seat_player(player).ok(),
+            _ => None,
+        };
+        if let (Some(seat), Some(escrow)) = (seat, match_data.escrow.as_mut()) {
+            escrow.deposit(seat, account);
+        }
+        let seated = seat.is_some();
 
-            let player_count = match_data.players.iter()
-                .filter(|p| p.is_some()).count();
+        // Send confirmation back to USER_CHAIN (triggers subscribe on success)
+        let confirmation = Message::JoinMatchConfirmed {
+            play_chain_id,
+            success: seated,
+        };
+        self.runtime.prepare_message(confirmation).send_to(player_chain);
 
-            // Send confirmation back to USER_CHAIN (triggers subscribe!)
-            let confirmation = Message::JoinMatchConfirmed {
-                play_chain_id,
-                success: true,
-            };
-            self.runtime.prepare_message(confirmation).send_to(player_chain);
+        if seated {
+            let player_count = self.state.match_data.get().player_count();
 
             // Emit event
             self.emit_event(GameEvent::PlayerJoined {
@@ -247,431 +237,564 @@ match_data.discard_pile.push(card);
 
             log::info!("PLAY_CHAIN: Player joined from chain: {:?}, sent confirmation", origin_chain);
         } else {
-            // Send failure confirmation
+            if paid.is_ok() {
+                log::warn!("PLAY_CHAIN: No empty slots for player to join");
+            }
+            if let Some(stake) = stake {
+                self.refund_payment(stake, account);
+            }
+        }
+    }
+
+    /// Seat a bot in an empty slot (host only, while waiting for players)
+    pub async fn handle_add_bot(
+        &mut self,
+        player_owner: Option<AccountOwner>,
+        seat: usize,
+        difficulty: BotDifficulty,
+    ) -> LinotResponse {
+        let chain_id = self.runtime.chain_id();
+        let match_data = self.state.match_data.get_mut();
+
+        if !player_owner.is_some_and(|owner| match_data.is_host(&owner)) {
+            log::warn!("PLAY_CHAIN: AddBot rejected - not the host");
+            return LinotResponse::Error(GameError::NotHost.to_string());
+        }
+        if match_data.escrow.is_some() {
+            log::warn!("PLAY_CHAIN: AddBot rejected - {}", EscrowError::BotSeat);
+            return LinotResponse::Error(EscrowError::BotSeat.to_string());
+        }
+
+        let player = Player::new_bot(chain_id, seat, difficulty);
+        let nickname = player.nickname.clone();
+        if let Err(error) = match_data.seat_player_at(seat, player) {
+            log::warn!("PLAY_CHAIN: AddBot rejected - {}", error);
+            return LinotResponse::Error(error.to_string());
+        }
+        let player_count = match_data.player_count();
+
+        self.emit_event(GameEvent::PlayerJoined {
+            nickname,
+            player_count,
+        });
+
+        log::info!("PLAY_CHAIN: {:?} bot seated at {}", difficulty, seat);
+        LinotResponse::Ok
+    }
+
+    /// Call off a match that hasn't started and hand every entry fee back (host only)
+    pub async fn handle_cancel_match(&mut self, player_owner: Option<AccountOwner>) -> LinotResponse {
+        let match_data = self.state.match_data.get_mut();
+
+        if !player_owner.is_some_and(|owner| match_data.is_host(&owner)) {
+            log::warn!("PLAY_CHAIN: CancelMatch rejected - not the host");
+            return LinotResponse::Error(GameError::NotHost.to_string());
+        }
+        if match_data.status != MatchStatus::Waiting {
+            log::warn!("PLAY_CHAIN: CancelMatch rejected - match already started");
+            return LinotResponse::Error(GameError::AlreadyStarted.to_string());
+        }
+
+        match_data.status = MatchStatus::Finished;
+        let refunds = match_data.escrow.as_mut().map(|escrow| {
+            escrow.settled = true;
+            (escrow.stake.token, escrow.refunds())
+        });
+
+        let host = self.seat_nickname(0);
+        log::info!("PLAY_CHAIN: Match called off by {}", host);
+        self.emit_event(GameEvent::MatchCancelled { host });
+
+        if let Some((token, refunds)) = refunds {
+            self.send_from_escrow(token, &refunds);
+            self.emit_event(GameEvent::EntryFeesRefunded { refunds });
+        }
+        LinotResponse::Ok
+    }
+
+    /// Give up a seat while the lobby is open, refunding its entry fee (not the host - they cancel)
+    pub async fn handle_leave_lobby(&mut self, player_owner: AccountOwner) -> LinotResponse {
+        let match_data = self.state.match_data.get_mut();
+
+        let Some(seat) = match_data.seat_of(&player_owner) else {
+            log::warn!("PLAY_CHAIN: LeaveLobby rejected - {}", GameError::InvalidSeat);
+            return LinotResponse::Error(GameError::InvalidSeat.to_string());
+        };
+        if seat == 0 {
+            log::warn!("PLAY_CHAIN: LeaveLobby rejected - the host cancels the match instead");
+            return LinotResponse::Error("The host cancels the match instead of leaving".to_string());
+        }
+        let player = match match_data.vacate_seat(seat) {
+            Ok(player) => player,
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: LeaveLobby rejected - {}", error);
+                return LinotResponse::Error(error.to_string());
+            }
+        };
+        let refund = match_data.escrow.as_mut().and_then(|escrow| {
+            escrow.withdraw(seat).map(|refund| (escrow.stake.token, refund))
+        });
+        let player_count = match_data.player_count();
+
+        log::info!("PLAY_CHAIN: {} left seat {}", player.nickname, seat);
+        self.emit_event(GameEvent::PlayerLeftLobby {
+            nickname: player.nickname,
+            player_count,
+        });
+
+        if let Some((token, refund)) = refund {
+            let refunds = vec![refund];
+            self.send_from_escrow(token, &refunds);
+            self.emit_event(GameEvent::EntryFeesRefunded { refunds });
+        }
+        LinotResponse::Ok
+    }
+
+    /// Split the seats into teams (host only, while waiting for players)
+    pub async fn handle_set_teams(
+        &mut self,
+        player_owner: Option<AccountOwner>,
+        teams: Vec<u8>,
+    ) -> LinotResponse {
+        let match_data = self.state.match_data.get_mut();
+
+        if !player_owner.is_some_and(|owner| match_data.is_host(&owner)) {
+            log::warn!("PLAY_CHAIN: SetTeams rejected - not the host");
+            return LinotResponse::Error(GameError::NotHost.to_string());
+        }
+
+        if let Err(error) = match_data.set_teams(teams.clone()) {
+            log::warn!("PLAY_CHAIN: SetTeams rejected - {}", error);
+            return LinotResponse::Error(error.to_string());
+        }
+
+        log::info!("PLAY_CHAIN: Teams set to {:?}", teams);
+        self.emit_event(GameEvent::TeamsAssigned { teams });
+        LinotResponse::Ok
+    }
+
+    /// Seat a tournament table sent by the TOURNAMENT_CHAIN and start its match
+    pub async fn handle_open_table_message(
+        &mut self,
+        tournament_chain_id: ChainId,
+        round: u8,
+        table: usize,
+        players: Vec<Player>,
+        time_control: TimeControl,
+        rules: RuleSet,
+    ) {
+        let origin_chain = self.runtime.message_origin_chain_id()
+            .expect("Message has no origin");
+        if origin_chain != tournament_chain_id || !self.accepts_tables_from(origin_chain) {
+            log::warn!("PLAY_CHAIN: OpenTable rejected - {:?} is not this table's coordinator", origin_chain);
+            return;
+        }
+        // Never seat a table over a lobby, a running match or fees still in the pot
+        let match_data = self.state.match_data.get();
+        if match_data.status != MatchStatus::Finished && !match_data.players.is_empty() {
+            log::warn!("PLAY_CHAIN: OpenTable rejected - a match is already open");
+            return;
+        }
+        if self.escrow_outstanding() {
+            log::warn!("PLAY_CHAIN: OpenTable rejected - {}", EscrowError::PotOutstanding);
+            return;
+        }
+        if players.len() < MIN_PLAYERS as usize || players.len() > MAX_PLAYERS as usize {
+            log::warn!("PLAY_CHAIN: OpenTable rejected - {} players", players.len());
+            return;
+        }
+
+        let seed = self.runtime.system_time().micros();
+        let max_players = players.len() as u8;
+        let mut match_data = MatchData::new_lobby_with_time_control(max_players, seed, time_control);
+        match_data.match_id = self.next_match_id();
+        match_data.rules = rules;
+        let player_chains: Vec<ChainId> = players.iter().map(|player| player.chain_id).collect();
+        let host = players[0].nickname.clone();
+        for (seat, player) in players.into_iter().enumerate() {
+            match_data.players[seat] = Some(player);
+        }
+        self.state.match_data.set(match_data);
+
+        self.state.table_coordinator.set(Some(origin_chain));
+
+        let play_chain_id = self.runtime.chain_id();
+        self.emit_event(GameEvent::MatchCreated {
+            match_id: play_chain_id,
+            host,
+            max_players,
+        });
+
+        // Every entrant follows the table like a player who joined it
+        for player_chain in player_chains {
             let confirmation = Message::JoinMatchConfirmed {
                 play_chain_id,
-                success: false,
+                success: true,
             };
             self.runtime.prepare_message(confirmation).send_to(player_chain);
+        }
 
-            log::warn!("PLAY_CHAIN: No empty slots for player to join");
+        log::info!(
+            "PLAY_CHAIN: Opened table {} of round {} for tournament {:?}",
+            table, round, tournament_chain_id,
+        );
+        self.handle_start_match(None).await;
+    }
+
+    /// Tables come from the coordinator that opened this chain. The runtime doesn't
+    /// name a chain's parent, so the first coordinator to seat a table on an untouched
+    /// chain - always the one that just opened it - becomes the only one accepted.
+    fn accepts_tables_from(&self, coordinator: ChainId) -> bool {
+        match *self.state.table_coordinator.get() {
+            Some(table_coordinator) => table_coordinator == coordinator,
+            None => {
+                self.state.match_data.get().players.is_empty()
+                    && self.state.subscribed_play_chain.get().is_none()
+                    && self.state.spectated_play_chain.get().is_none()
+                    && self.state.tournament_chain.get().is_none()
+                    && self.state.tournament.get().is_none()
+            }
         }
     }
 
+    /// Handle spectate request from USER_CHAIN (no seat is taken)
+    pub async fn handle_request_spectate_message(&mut self, spectator_chain: ChainId) {
+        let play_chain_id = self.runtime.chain_id();
+        let match_data = self.state.match_data.get_mut();
+
+        if match_data.players.is_empty() {
+            // Nothing to watch yet
+            self.runtime.prepare_message(Message::SpectateConfirmed {
+                play_chain_id,
+                snapshot: None,
+            }).send_to(spectator_chain);
+            log::warn!("PLAY_CHAIN: Spectate rejected - no match on this chain");
+            return;
+        }
+
+        let is_seated = match_data.players.iter()
+            .flatten()
+            .any(|player| player.chain_id == spectator_chain);
+        if !is_seated && !match_data.spectators.contains(&spectator_chain) {
+            match_data.spectators.push(spectator_chain);
+        }
+        match_data.spectator_count = match_data.spectators.len();
+
+        let spectator_count = match_data.spectator_count;
+        let snapshot = match_data.public_view();
+
+        // Borrow ends here
+
+        self.runtime.prepare_message(Message::SpectateConfirmed {
+            play_chain_id,
+            snapshot: Some(Box::new(snapshot)),
+        }).send_to(spectator_chain);
+
+        self.emit_event(GameEvent::SpectatorCountChanged { spectator_count });
+
+        log::info!("PLAY_CHAIN: Spectator {:?} watching ({} total)", spectator_chain, spectator_count);
+    }
+
+    /// Handle spectator leaving
+    pub async fn handle_stop_spectating_message(&mut self, spectator_chain: ChainId) {
+        let match_data = self.state.match_data.get_mut();
+
+        let before = match_data.spectators.len();
+        match_data.spectators.retain(|chain_id| *chain_id != spectator_chain);
+        if match_data.spectators.len() == before {
+            return;
+        }
+        match_data.spectator_count = match_data.spectators.len();
+        let spectator_count = match_data.spectator_count;
+
+        self.emit_event(GameEvent::SpectatorCountChanged { spectator_count });
+
+        log::info!("PLAY_CHAIN: Spectator {:?} left ({} remaining)", spectator_chain, spectator_count);
+    }
+
     /// Handle play card action from USER_CHAIN
     pub async fn handle_play_card_message(
         &mut self,
         player_owner: AccountOwner,
-        card_index: usize,
-        chosen_suit: Option<CardSuit>
+        card_id: u8,
+        chosen_suit: Option<CardSuit>,
+        expected_top_card_id: Option<u8>,
+        turn_number: u64,
+    ) {
+        self.handle_play_cards_message(player_owner, vec![card_id], chosen_suit, expected_top_card_id, turn_number).await;
+    }
+
+    /// Handle a multi-card play from USER_CHAIN: same-value cards dropped in one move
+    pub async fn handle_play_cards_message(
+        &mut self,
+        player_owner: AccountOwner,
+        card_ids: Vec<u8>,
+        chosen_suit: Option<CardSuit>,
+        expected_top_card_id: Option<u8>,
+        turn_number: u64,
     ) {
+        let now = self.now();
+        let action = if card_ids.len() == 1 { "PlayCard" } else { "PlayCards" };
         let match_data = self.state.match_data.get_mut();
 
-        // Check if player has pending penalty cards
-        // They must handle the penalty BEFORE playing (unless they're blocking with same card type)
-        if match_data.pending_draw_stack > 0 {
-            let current_idx = match_data.current_player_index;
-            
-            if let Some(current_player) = match_data.players[current_idx].as_ref() {
-                // Check if the card they're trying to play can block the penalty
-                if card_index < current_player.hand.len() {
-                    let card = &current_player.hand[card_index];
-                    let can_block = match match_data.pending_draw_type {
-                        Some(2) => card.value == 2,  // Can block Pick 2 with another Pick 2
-                        Some(5) => card.value == 5 && card.suit != CardSuit::Star,  // Can block Pick 3 with another Pick 3 (not Star 5)
-                        _ => false,
-                    };
-                    
-                    if !can_block {
-                        // Cannot block - must draw penalty cards first
-                        let draw_count = match_data.pending_draw_stack;
-                        let current_player = match_data.players[current_idx].as_mut().unwrap();
-                        
-                        log::info!("PLAY_CHAIN: {} must draw {} penalty cards before playing", current_player.nickname, draw_count);
-                        
-                        // Draw all penalty cards
-                        for _ in 0..draw_count {
-                            if match_data.deck.is_empty() {
-                                log::info!("PLAY_CHAIN: Deck empty during penalty draw");
-                                break;
-                            }
-                            
-                            if let Some(card) = match_data.deck.pop() {
-                                current_player.hand.push(card);
-                            }
-                        }
-                        
-                        current_player.update_hand_size();
-                        match_data.deck_size = match_data.deck.len();
-                        
-                        // Reset penalty
-                        match_data.pending_draw_stack = 0;
-                        match_data.pending_draw_type = None;
-                        
-                        log::info!("PLAY_CHAIN: Penalty applied, player can now continue their turn");
-                        // DON'T return here - let them play their card
-                    }
-                }
-            }
+        let Some(seat) = match_data.seat_of(&player_owner) else {
+            log::warn!("PLAY_CHAIN: {} from a player not in this match", action);
+            return;
+        };
+        if let Err(error) = match_data.check_turn_number(turn_number) {
+            log::warn!("PLAY_CHAIN: {} rejected - {}", action, error);
+            return;
         }
 
-        // Find player and verify it's their turn
-        let current_player_opt = match_data.players[match_data.current_player_index].as_mut();
-        
-        if let Some(current_player) = current_player_opt {
-            if current_player.owner != player_owner {
-                log::warn!("PLAY_CHAIN: Not the current player's turn");
-                return;
-            }
-
-            // Validate card index
-            if card_index >= current_player.hand.len() {
-                log::warn!("PLAY_CHAIN: Invalid card index");
+        let outcome = match match_data.play_cards_by_id(seat, &card_ids, chosen_suit, expected_top_card_id, now) {
+            Ok(outcome) => outcome,
+            Err(GameError::OutOfTime) => {
+                self.handle_out_of_time().await;
                 return;
             }
-
-            let card = current_player.hand[card_index].clone();
-            
-            // Validate play - check against active demand suit or top card
-            let is_valid = if let Some(demand_suit) = match_data.active_demand_suit {
-                // WHOT was played - match against chosen suit
-                card.value == 20  // WHOT can always be played
-                    || card.suit == demand_suit
-            } else if let Some(top) = match_data.discard_pile.last() {
-                // Normal validation against top card
-                card.value == 20  // Whot card
-                    || card.suit == top.suit
-                    || card.value == top.value
-            } else {
-                // First card - always valid
-                true
-            };
-            
-            if !is_valid {
-                log::warn!("PLAY_CHAIN: Invalid card play");
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: {} rejected - {}", action, error);
                 return;
             }
+        };
 
-            // Remove card from hand
-            current_player.hand.remove(card_index);
-            current_player.update_hand_size();
+        if outcome.penalty_drawn > 0 {
+            log::info!("PLAY_CHAIN: {} penalty cards drawn before playing", outcome.penalty_drawn);
+        }
 
-            // Add to discard pile
-            match_data.discard_pile.push(card.clone());
+        let mut record = MoveRecord::new(seat, MoveKind::Play, now);
+        record.card = Some(outcome.card.clone());
+        record.more_cards = outcome.more_cards.clone();
+        record.chosen_suit = chosen_suit.filter(|_| outcome.card.value == WHOT_VALUE);
+        record.cards_drawn = outcome.penalty_drawn;
+        record.effect = outcome.special_effect.clone();
+        self.record_move(record).await;
 
-            // Check win condition
-            if current_player.hand.is_empty() {
-                match_data.status = MatchStatus::Finished;
-                match_data.winner_index = Some(match_data.current_player_index);
-                
-                let winner_name = current_player.nickname.clone();
-                let winner_idx = match_data.current_player_index;
-                
-                // Borrow ends here
+        let player_nickname = self.seat_nickname(seat);
 
-                self.emit_event(GameEvent::MatchEnded {
-                    winner: winner_name,
-                    winner_index: winner_idx,
+        // Check win condition
+        if let Some(winner_index) = outcome.winner {
+            if let Some(announcement) = outcome.announcement {
+                self.emit_event(GameEvent::HandAnnounced {
+                    player_nickname: player_nickname.clone(),
+                    announcement,
                 });
-                return;
-            }
-
-            // Get special effect - inline to avoid borrow conflicts
-            let special_effect = match card.value {
-                2 => Some(SpecialEffect::PickTwo),
-                5 if card.suit != CardSuit::Star => Some(SpecialEffect::PickThree),
-                1 => Some(SpecialEffect::HoldOn),
-                14 => Some(SpecialEffect::GeneralMarket),
-                20 => chosen_suit.map(|suit| SpecialEffect::WhotPlayed { chosen_suit: suit }),
-                _ => None,
-            };
-            
-            // Update active demand suit
-            if card.value == 20 {
-                // WHOT played - set demand suit
-                match_data.active_demand_suit = chosen_suit;
-                log::info!("PLAY_CHAIN: WHOT played, demand suit set to {:?}", chosen_suit);
-            } else {
-                // Regular card - clear demand suit
-                match_data.active_demand_suit = None;
             }
+            let winning_team = self.state.match_data.get().winning_team;
+            self.emit_event(GameEvent::MatchEnded {
+                winner: player_nickname,
+                winner_index,
+                winning_team,
+            });
+            return;
+        }
 
-            // Collect data BEFORE advancing turn
-            let current_index = match_data.current_player_index;
-            let current_nickname = current_player.nickname.clone();
-            
-            // Calculate next player manually to avoid borrow conflicts
-            let mut next_index = (current_index + 1) % match_data.players.len();
-            while match_data.players[next_index].is_none() {
-                next_index = (next_index + 1) % match_data.players.len();
-            }
-            
-            let next_player_name = match_data.players[next_index].as_ref()
-                .map(|p| p.nickname.clone())
-                .unwrap_or_default();
-            
-            match_data.current_player_index = next_index;
-            
-            // Reset turn timer
-            match_data.turn_start_time = Some(self.runtime.system_time().micros());
-            
-            // Borrow ends here
+        let next_player = self.seat_nickname(outcome.next_seat);
+        let whot_demand = self.state.match_data.get().whot_demand.clone();
+        if let Some(effect) = &outcome.special_effect {
+            log::info!("PLAY_CHAIN: Special effect {:?}, pending draw: {}", effect, self.state.match_data.get().pending_total());
+        }
 
-            // Emit event
+        // Emit event - single plays keep the CardPlayed shape
+        let market_draws = outcome.market_draws;
+        if outcome.more_cards.is_empty() {
             self.emit_event(GameEvent::CardPlayed {
-                player_nickname: current_nickname.clone(),
-                card: card.clone(),
-                next_player: next_player_name,
-                special_effect: special_effect.clone(),
+                player_nickname: player_nickname.clone(),
+                card: outcome.card,
+                next_player,
+                special_effect: outcome.special_effect,
+                whot_demand,
             });
+        } else {
+            let mut cards = vec![outcome.card];
+            cards.extend(outcome.more_cards);
+            self.emit_event(GameEvent::CardsPlayed {
+                player_nickname: player_nickname.clone(),
+                cards,
+                next_player,
+                special_effect: outcome.special_effect,
+                whot_demand,
+            });
+        }
 
-            // NOW EXECUTE THE SPECIAL EFFECT
-            // Pass the index of the player who played the card for effects that need it (like General Market)
-            self.execute_special_effect(special_effect, current_index);
-
-            log::info!("PLAY_CHAIN: Card played successfully");
+        if let Some(announcement) = outcome.announcement {
+            self.emit_event(GameEvent::HandAnnounced {
+                player_nickname: player_nickname.clone(),
+                announcement,
+            });
+        }
+        if !market_draws.is_empty() {
+            self.emit_event(GameEvent::GeneralMarketResolved {
+                player_nickname,
+                draws: market_draws,
+            });
         }
+
+        log::info!("PLAY_CHAIN: Card played successfully");
     }
 
     /// Handle draw card action from USER_CHAIN
-    pub async fn handle_draw_card_message(&mut self, player_owner: AccountOwner) {
+    pub async fn handle_draw_card_message(&mut self, player_owner: AccountOwner, turn_number: u64) {
+        let now = self.now();
         let match_data = self.state.match_data.get_mut();
 
-        let current_player_opt = match_data.players[match_data.current_player_index].as_mut();
-        
-        if let Some(current_player) = current_player_opt {
-            if current_player.owner != player_owner {
-                log::warn!("PLAY_CHAIN: Not the current player's turn");
-                return;
-            }
-
-            // Check if there's a pending penalty
-            let penalty_count = if match_data.pending_draw_stack > 0 {
-                let count = match_data.pending_draw_stack;
-                log::info!("PLAY_CHAIN: Player drawing with pending penalty of {}", count);
-                
-                // Reset penalty
-                match_data.pending_draw_stack = 0;
-                match_data.pending_draw_type = None;
-                
-                count
-            } else {
-                0
-            };
+        let Some(seat) = match_data.seat_of(&player_owner) else {
+            log::warn!("PLAY_CHAIN: DrawCard from a player not in this match");
+            return;
+        };
+        if let Err(error) = match_data.check_turn_number(turn_number) {
+            log::warn!("PLAY_CHAIN: DrawCard rejected - {}", error);
+            return;
+        }
 
-            // Check if deck is empty - check if player has any valid cards to play
-            if match_data.deck.is_empty() {
-                log::info!("PLAY_CHAIN: Deck is empty, checking if player can play any card");
-                
-                // Check if current player has any valid card to play
-                let top_card = match_data.discard_pile.last();
-                let demand_suit = match_data.active_demand_suit;
-                
-                let can_play = current_player.hand.iter().any(|card| {
-                    if let Some(demand_suit) = demand_suit {
-                        // WHOT was played - check against chosen suit
-                        card.value == 20 || card.suit == demand_suit
-                    } else if let Some(top) = top_card {
-                        // Normal validation
-                        card.value == 20 || card.suit == top.suit || card.value == top.value
-                    } else {
-                        // No top card - any card valid
-                        true
-                    }
-                });
-                
-                if !can_play {
-                    log::info!("PLAY_CHAIN: Deck empty and player has no valid cards - ending game");
-                    
-                    // Count cards for all players
-                    let mut card_counts: Vec<(usize, usize)> = Vec::new();
-                    for (idx, player_opt) in match_data.players.iter().enumerate() {
-                        if let Some(player) = player_opt {
-                            card_counts.push((idx, player.hand.len()));
-                        }
-                    }
-                    
-                    // Find minimum card count
-                    let min_count = card_counts.iter().map(|(_, count)| *count).min().unwrap_or(0);
-                    
-                    // Find all players with minimum count (for draw detection)
-                    let winners: Vec<usize> = card_counts.iter()
-                        .filter(|(_, count)| *count == min_count)
-                        .map(|(idx, _)| *idx)
-                        .collect();
-                    
-                    if winners.len() > 1 {
-                        // DRAW - multiple players with same card count
-                        match_data.status = MatchStatus::Finished;
-                        match_data.winner_index = None;
-                        log::info!("PLAY_CHAIN: Game ended in DRAW - {} players with {} cards each", 
-                                   winners.len(), min_count);
-                        
-                        self.emit_event(GameEvent::MatchEnded {
-                            winner: "Draw".to_string(),
-                            winner_index: usize::MAX, // Special value for draw
-                        });
-                    } else if let Some(&winner_idx) = winners.first() {
-                        // Single winner - player with fewest cards
-                        match_data.status = MatchStatus::Finished;
-                        match_data.winner_index = Some(winner_idx);
-                        
-                        let winner_name = match_data.players[winner_idx].as_ref()
-                            .map(|p| p.nickname.clone())
-                            .unwrap_or_default();
-                        
-                        log::info!("PLAY_CHAIN: Game ended - {} wins with {} cards", 
-                                   winner_name, min_count);
-                        
-                        self.emit_event(GameEvent::MatchEnded {
-                            winner: winner_name,
-                            winner_index: winner_idx,
-                        });
-                    }
-                    
-                    return;
-                }
-                
-                log::warn!("PLAY_CHAIN: Deck empty but player has valid cards to play - cannot draw");
+        let outcome = match match_data.draw_card(seat, now) {
+            Ok(outcome) => outcome,
+            Err(GameError::OutOfTime) => {
+                self.handle_out_of_time().await;
                 return;
             }
-            
-            // Draw cards: 1 (manual) + penalty_count
-            let total_draw = 1 + penalty_count;
-            let mut actually_drawn = 0;
-            
-            for _ in 0..total_draw {
-                if match_data.deck.is_empty() {
-                    log::info!("PLAY_CHAIN: Deck empty after drawing {} cards", actually_drawn);
-                    break;
-                }
-                
-                if let Some(card) = match_data.deck.pop() {
-                    current_player.hand.push(card);
-                    actually_drawn += 1;
-                }
-            }
-            
-            if actually_drawn == 0 {
-                log::warn!("PLAY_CHAIN: Cannot draw - deck empty");
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: DrawCard rejected - {}", error);
                 return;
             }
-            
-            current_player.update_hand_size();
-            current_player.called_last_card = false;  // Reset flag after drawing
-            match_data.deck_size = match_data.deck.len();
+        };
 
-            log::info!("PLAY_CHAIN: Player drew {} card(s) (1 manual{}) ", 
-                      actually_drawn,
-                      if penalty_count > 0 { format!(" + {} penalty", penalty_count) } else { String::new() });
+        let mut record = MoveRecord::new(seat, MoveKind::Draw, now);
+        if let DrawOutcome::Drawn { count, .. } = outcome {
+            record.cards_drawn = count;
+        }
+        self.record_move(record).await;
 
-            // Collect data before advancing
-            let current_index = match_data.current_player_index;
-            let current_nickname = current_player.nickname.clone();
-            
-            // Calculate next player inline to avoid borrow conflicts
-            let mut next_index = (current_index + 1) % match_data.players.len();
-            while match_data.players[next_index].is_none() {
-                next_index = (next_index + 1) % match_data.players.len();
+        match outcome {
+            DrawOutcome::Drawn { count, penalty, next_seat } => {
+                log::info!("PLAY_CHAIN: Player drew {} card(s) (1 manual{}) ",
+                          count,
+                          if penalty > 0 { format!(" + {} penalty", penalty) } else { String::new() });
+
+                let player_nickname = self.seat_nickname(seat);
+                let next_player = self.seat_nickname(next_seat);
+                self.emit_event(GameEvent::CardsDrawn {
+                    player_nickname,
+                    count,
+                    next_player,
+                });
             }
-            
-            let next_player_name = match_data.players[next_index].as_ref()
-                .map(|p| p.nickname.clone())
-                .unwrap_or_default();
-            
-            match_data.current_player_index = next_index;
-            
-            // Reset turn timer
-            match_data.turn_start_time = Some(self.runtime.system_time().micros());
-            
-            // Borrow ends here
 
-            // Emit event - always report as 1 for frontend consistency (penalty is separate)
-            self.emit_event(GameEvent::CardsDrawn {
-                player_nickname: current_nickname,
-                count: actually_drawn,
-                next_player: next_player_name,
-            });
+            DrawOutcome::DeckExhausted { winner: None } => {
+                log::info!("PLAY_CHAIN: Deck empty and player has no valid cards - game ended in DRAW");
+                self.emit_event(GameEvent::MatchEnded {
+                    winner: "Draw".to_string(),
+                    winner_index: usize::MAX, // Special value for draw
+                    winning_team: None,
+                });
+            }
 
+            DrawOutcome::DeckExhausted { winner: Some(winner_index) } => {
+                let winner = self.seat_nickname(winner_index);
+                let winning_team = self.state.match_data.get().winning_team;
+                match winning_team {
+                    Some(team) => log::info!("PLAY_CHAIN: Deck empty and player has no valid cards - team {} wins on card count", team),
+                    None => log::info!("PLAY_CHAIN: Deck empty and player has no valid cards - {} wins on card count", winner),
+                }
+                self.emit_event(GameEvent::MatchEnded {
+                    winner,
+                    winner_index,
+                    winning_team,
+                });
+            }
         }
     }
 
     /// Handle call last card action
-    pub async fn handle_call_last_card_message(&mut self, player_owner: AccountOwner) {
+    pub async fn handle_call_last_card_message(&mut self, player_owner: AccountOwner, last_card_seq: u64) {
+        let now = self.now();
         let match_data = self.state.match_data.get_mut();
 
         // Find player by owner and set flag
-        let mut player_nickname_opt = None;
-        for player_opt in match_data.players.iter_mut() {
-            if let Some(player) = player_opt {
-                if player.owner == player_owner {
-                    player.called_last_card = true;
-                    player_nickname_opt = Some(player.nickname.clone());
-                    break;
-                }
-            }
+        let Some(seat) = match_data.seat_of(&player_owner) else {
+            return;
+        };
+        if let Err(error) = match_data.check_last_card_seq(last_card_seq) {
+            log::warn!("PLAY_CHAIN: CallLastCard rejected - {}", error);
+            return;
         }
-        
-        // Borrow ends here
-        
+        let outcome = match match_data.call_last_card(seat) {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: CallLastCard rejected - {}", error);
+                return;
+            }
+        };
+
+        let mut record = MoveRecord::new(seat, MoveKind::CallLastCard, now);
+        record.cards_drawn = outcome.penalty_cards;
+        self.record_move(record).await;
+
         // Emit event
-        if let Some(nickname) = player_nickname_opt {
-            let log_nickname = nickname.clone();
-            self.emit_event(GameEvent::LastCardCalled {
+        let nickname = self.seat_nickname(seat);
+        if !outcome.accepted {
+            log::info!("PLAY_CHAIN: {} called last card holding {} cards - draws {}", nickname, outcome.hand_size, outcome.penalty_cards);
+            self.emit_event(GameEvent::LastCardRejected {
                 player_nickname: nickname,
+                hand_size: outcome.hand_size,
+                penalty_cards: outcome.penalty_cards,
             });
-            log::info!("PLAY_CHAIN: {} called last card", log_nickname);
+            return;
         }
+        log::info!("PLAY_CHAIN: {} called last card", nickname);
+        self.emit_event(GameEvent::LastCardCalled {
+            player_nickname: nickname,
+        });
     }
 
     /// Handle challenge last card action
     pub async fn handle_challenge_last_card_message(
         &mut self,
-        _challenger_owner: AccountOwner,
-        challenged_player_index: usize
+        challenger_owner: AccountOwner,
+        challenged_player_index: usize,
+        last_card_seq: u64,
     ) {
+        let now = self.now();
         let match_data = self.state.match_data.get_mut();
-        
-        if challenged_player_index >= match_data.players.len() {
-            log::warn!("PLAY_CHAIN: Invalid player index for challenge");
+
+        // Only seated players can challenge - the move log needs an actor
+        let Some(challenger_seat) = match_data.seat_of(&challenger_owner) else {
+            log::warn!("PLAY_CHAIN: Challenge from a player not in this match");
+            return;
+        };
+        if let Err(error) = match_data.check_last_card_seq(last_card_seq) {
+            log::warn!("PLAY_CHAIN: Challenge rejected - {}", error);
             return;
         }
-        
-        if let Some(challenged_player) = match_data.players[challenged_player_index].as_mut() {
-            // Check if player has exactly 1 card and DIDN'T call last card
-            if challenged_player.hand.len() == 1 && !challenged_player.called_last_card {
-                let player_nickname = challenged_player.nickname.clone();
-                
-                // PENALTY: Draw 2 cards (or as many as available)
-                for _ in 0..2 {
-                    // Check if deck is empty
-                    if match_data.deck.is_empty() {
-                        log::info!("PLAY_CHAIN: Deck empty during challenge penalty");
-                        challenged_player.update_hand_size();
-                        match_data.deck_size = 0;
-                        break;
-                    }
-                    
-                    if let Some(card) = match_data.deck.pop() {
-                        challenged_player.hand.push(card);
-                    }
-                }
-                
-                challenged_player.update_hand_size();
-                match_data.deck_size = match_data.deck.len();
-                
-                // Borrow ends here
-                
-                self.emit_event(GameEvent::ChallengePenalty {
-                    challenged_player: player_nickname.clone(),
-                    penalty_cards: 2,
-                });
-                
-                log::info!("PLAY_CHAIN: Challenge successful - {} draws 2 cards", player_nickname);
-            } else {
-                log::info!("PLAY_CHAIN: Challenge failed - player called last card or doesn't have 1 card");
+
+        let outcome = match match_data.challenge_last_card(challenger_seat, challenged_player_index) {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: Challenge rejected - {}", error);
+                return;
             }
+        };
+
+        let kind = if outcome.success { MoveKind::Challenge } else { MoveKind::FalseChallenge };
+        let mut record = MoveRecord::new(challenger_seat, kind, now);
+        record.target_seat = Some(challenged_player_index);
+        record.cards_drawn = outcome.penalty_cards;
+        self.record_move(record).await;
+
+        let challenger = self.seat_nickname(challenger_seat);
+        let challenged = self.seat_nickname(challenged_player_index);
+        if outcome.success {
+            log::info!("PLAY_CHAIN: Challenge successful - {} draws {} cards", challenged, outcome.penalty_cards);
+        } else {
+            log::info!("PLAY_CHAIN: Challenge failed - {} draws {} cards", challenger, outcome.penalty_cards);
         }
+
+        self.emit_event(GameEvent::ChallengeResolved {
+            challenger,
+            challenged,
+            success: outcome.success,
+            penalty: outcome.penalty_cards,
+        });
     }
 
     /// Check if current turn has timed out
@@ -684,20 +807,23 @@ match_data.discard_pile.push(card);
         
         if let Some(start_time) = match_data.turn_start_time {
             let current_time = self.runtime.system_time().micros();
-            let elapsed = current_time - start_time;
+            let elapsed = current_time.saturating_sub(start_time);
             
-            if elapsed > linot::TURN_TIMEOUT_MICROS {
-                // Timeout - auto-draw and advance turn
-                let _ = match_data;
-                self.handle_turn_timeout().await;
-            } else if elapsed > linot::TURN_WARNING_MICROS {
+            let allotment = match_data.turn_allotment();
+            
+            if elapsed > allotment {
+                if match_data.clock.is_some() {
+                    // Time bank spent - the player is out
+                    self.handle_out_of_time().await;
+                } else {
+                    // Timeout - auto-draw and advance turn
+                    self.handle_turn_timeout().await;
+                }
+                self.run_bot_turns().await;
+            } else if elapsed > match_data.turn_warning_micros() {
                 // Warning - time running out
-                let player_nickname = match_data.players[match_data.current_player_index]
-                    .as_ref()
-                    .map(|p| p.nickname.clone())
-                    .unwrap_or_default();
-                let time_left = linot::TURN_TIMEOUT_MICROS - elapsed;
-                let _ = match_data;
+                let player_nickname = self.seat_nickname(match_data.current_player_index);
+                let time_left = allotment - elapsed;
                 
                 self.emit_event(GameEvent::TurnWarning {
                     player_nickname,
@@ -709,55 +835,373 @@ match_data.discard_pile.push(card);
     
     /// Handle turn timeout - auto-draw and advance turn
     async fn handle_turn_timeout(&mut self) {
-        let match_data = self.state.match_data.get_mut();
-        let current_idx = match_data.current_player_index;
-        
-        if let Some(current_player) = match_data.players[current_idx].as_mut() {
-            let player_nickname = current_player.nickname.clone();
-            
-            // Auto-draw one card
-            let mut drew = false;
-            
-            // Check deck and refill if needed
-            if match_data.deck.is_empty() && match_data.discard_pile.len() > 1 {
-                let top_card = match_data.discard_pile.pop();
-                match_data.deck.append(&mut match_data.discard_pile);
-                let len = match_data.deck.len();
-                for i in (1..len).rev() {
-                    let j = (i * 7 + 11) % (i + 1);
-                    match_data.deck.swap(i, j);
+        let now = self.now();
+        let outcome = match self.state.match_data.get_mut().timeout_turn(now) {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: Timeout not applied - {}", error);
+                return;
+            }
+        };
+
+        let mut record = MoveRecord::new(outcome.seat, MoveKind::Timeout, now);
+        record.cards_drawn = outcome.cards_drawn;
+        self.record_move(record).await;
+
+        let player_nickname = self.seat_nickname(outcome.seat);
+
+        if let Some(policy) = outcome.removed {
+            log::info!("PLAY_CHAIN: {} removed after {} timeouts in a row ({:?})",
+                      player_nickname, outcome.consecutive_timeouts, policy);
+            self.emit_event(GameEvent::PlayerRemovedForInactivity {
+                player_nickname,
+                replaced_by_bot: policy == InactivityPolicy::ReplaceWithBot,
+                next_player: self.seat_nickname(outcome.next_seat),
+            });
+            if let Some(winner_index) = outcome.winner {
+                self.emit_event(GameEvent::MatchEnded {
+                    winner: self.seat_nickname(winner_index),
+                    winner_index,
+                    winning_team: self.state.match_data.get().winning_team,
+                });
+            }
+            return;
+        }
+
+        log::info!("PLAY_CHAIN: Turn timeout - {} auto-drew {} ({} penalty, {} in a row), turn advanced",
+                  player_nickname, outcome.cards_drawn, outcome.penalty_served, outcome.consecutive_timeouts);
+
+        self.emit_event(GameEvent::TurnTimeout {
+            player_nickname,
+            cards_drawn: outcome.cards_drawn,
+            penalty_served: outcome.penalty_served,
+        });
+    }
+
+    /// Apply every turn that expired since the last activity on this chain
+    ///
+    /// Each expiry is applied at its own deadline, so chained timeouts (and
+    /// bots whose turn came up in between) play out as if someone had been
+    /// polling CheckTimeout all along.
+    pub async fn settle_overdue_turns(&mut self) {
+        let now = self.runtime.system_time().micros();
+
+        for _ in 0..MAX_SETTLED_TURNS {
+            let match_data = self.state.match_data.get();
+            if match_data.status != MatchStatus::InProgress {
+                break;
+            }
+            let Some(turn_start) = match_data.turn_start_time else {
+                break;
+            };
+
+            let bot_to_move = match_data.players.get(match_data.current_player_index)
+                .and_then(|p| p.as_ref())
+                .is_some_and(|player| player.is_bot());
+            if bot_to_move {
+                // Bots move as soon as their turn starts
+                let moves_before = self.move_count().await;
+                self.as_of = Some(turn_start);
+                self.run_bot_turns().await;
+                if self.move_count().await == moves_before {
+                    break;
+                }
+                continue;
+            }
+
+            let deadline = turn_start.saturating_add(match_data.turn_allotment());
+            if deadline >= now {
+                break;
+            }
+
+            // First instant the turn counts as expired
+            self.as_of = Some(deadline + 1);
+            if match_data.clock.is_some() {
+                self.handle_out_of_time().await;
+            } else {
+                self.handle_turn_timeout().await;
+            }
+        }
+
+        self.as_of = None;
+    }
+
+    /// Play every bot turn until a human is to move or the match ends
+    ///
+    /// Bot moves go through the regular handlers, so they are validated,
+    /// logged and emitted exactly like human moves.
+    pub async fn run_bot_turns(&mut self) {
+        for _ in 0..MAX_BOT_MOVES_PER_TRIGGER {
+            let moves_before = self.move_count().await;
+            let match_data = self.state.match_data.get();
+            if match_data.status != MatchStatus::InProgress {
+                return;
+            }
+            let seat = match_data.current_player_index;
+            let Some((owner, difficulty)) = match_data.players.get(seat)
+                .and_then(|p| p.as_ref())
+                .and_then(|player| player.bot.map(|difficulty| (player.owner, difficulty)))
+            else {
+                return;
+            };
+
+            // Same log length always gives the same choice
+            let seed = match_data.seed ^ moves_before.rotate_left(32);
+            let turn_number = match_data.turn_number;
+
+            match bot::choose_action(match_data, seat, difficulty, seed) {
+                BotAction::Play { card_id, chosen_suit } => {
+                    self.handle_play_card_message(owner, card_id, chosen_suit, None, turn_number).await;
                 }
-                match_data.discard_pile.clear();
-                if let Some(card) = top_card {
-                    match_data.discard_pile.push(card);
+                BotAction::Draw => {
+                    self.handle_draw_card_message(owner, turn_number).await;
                 }
             }
-            
-            if let Some(card) = match_data.deck.pop() {
-                current_player.hand.push(card);
-                current_player.update_hand_size();
-                match_data.deck_size = match_data.deck.len();
-                drew = true;
+
+            if self.move_count().await == moves_before {
+                log::warn!("PLAY_CHAIN: Bot at seat {} could not move", seat);
+                return;
             }
-            
-            // Advance turn
-            let mut next_index = (current_idx + 1) % match_data.players.len();
-            while match_data.players[next_index].is_none() {
-                next_index = (next_index + 1) % match_data.players.len();
+
+            // Stronger bots don't forget to announce their last card
+            let down_to_one = self.state.match_data.get().players[seat].as_ref()
+                .is_some_and(|player| player.hand.len() == 1 && !player.called_last_card);
+            if down_to_one && difficulty != BotDifficulty::Random {
+                let last_card_seq = self.state.match_data.get().last_card_seq;
+                self.handle_call_last_card_message(owner, last_card_seq).await;
             }
-            match_data.current_player_index = next_index;
-            
-            // Reset turn timer for next player
-            match_data.turn_start_time = Some(self.runtime.system_time().micros());
-            
-            let _ = match_data;
-            
-            self.emit_event(GameEvent::TurnTimeout {
-                player_nickname: player_nickname.clone(),
-                auto_drawn: drew,
+        }
+    }
+
+    /// Handle a spent time bank - the current player forfeits
+    async fn handle_out_of_time(&mut self) {
+        let now = self.now();
+        let match_data = self.state.match_data.get_mut();
+        let seat = match_data.current_player_index;
+        let outcome = match match_data.forfeit(seat, now) {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: Forfeit not applied - {}", error);
+                return;
+            }
+        };
+
+        self.record_move(MoveRecord::new(seat, MoveKind::Forfeit, now)).await;
+
+        let player_nickname = self.seat_nickname(seat);
+        log::info!("PLAY_CHAIN: {} ran out of time", player_nickname);
+
+        self.emit_event(GameEvent::PlayerOutOfTime {
+            player_nickname,
+            next_player: self.seat_nickname(outcome.next_seat),
+        });
+
+        if let Some(winner_index) = outcome.winner {
+            self.emit_event(GameEvent::MatchEnded {
+                winner: self.seat_nickname(winner_index),
+                winner_index,
+                winning_team: self.state.match_data.get().winning_team,
             });
-            
-            log::info!("PLAY_CHAIN: Turn timeout - {} auto-drew, turn advanced", player_nickname);
+        }
+    }
+
+    // ================================================================================
+    // CHAT (Table chat for players and spectators)
+    // ================================================================================
+
+    /// Post a chat message from `sender_chain` (a seated player's or a spectator's USER_CHAIN)
+    pub async fn handle_chat_message(&mut self, sender_chain: ChainId, nickname: Option<String>, text: String) -> LinotResponse {
+        match self.post_chat(sender_chain, nickname, &text).await {
+            Ok(entry) => {
+                log::info!("PLAY_CHAIN: Chat from {}: {}", entry.nickname, entry.text);
+                self.emit_event(GameEvent::ChatMessage { entry });
+                LinotResponse::Ok
+            }
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: Chat rejected - {}", error);
+                LinotResponse::Error(error.to_string())
+            }
+        }
+    }
+
+    /// Mute or unmute `chain_id` in the chat (host only)
+    pub async fn handle_mute_chat(
+        &mut self,
+        player_owner: Option<AccountOwner>,
+        chain_id: ChainId,
+        muted: bool,
+    ) -> LinotResponse {
+        if !player_owner.is_some_and(|owner| self.state.match_data.get().is_host(&owner)) {
+            log::warn!("PLAY_CHAIN: MuteChat rejected - not the host");
+            return LinotResponse::Error(GameError::NotHost.to_string());
+        }
+
+        let match_id = self.state.match_data.get().match_id;
+        self.state.muted_chats.get_mut().set(match_id, chain_id, muted);
+        log::info!("PLAY_CHAIN: Chat {} for {:?}", if muted { "muted" } else { "unmuted" }, chain_id);
+        LinotResponse::Ok
+    }
+
+    /// Check the sender and the text, then append the message to the match's chat log
+    async fn post_chat(&mut self, sender_chain: ChainId, nickname: Option<String>, text: &str) -> Result<ChatEntry, ChatError> {
+        let now = self.now();
+        let seat = self.chat_seat(sender_chain)?;
+        let match_id = self.state.match_data.get().match_id;
+        if self.state.muted_chats.get().is_muted(match_id, sender_chain) {
+            return Err(ChatError::Muted);
+        }
+        let text = chat::clean_text(text)?;
+
+        let mut window = self.state.chat_windows.get(&sender_chain).await
+            .expect("Failed to load chat window")
+            .unwrap_or_default();
+        window.try_send(now)?;
+        self.state.chat_windows.insert(&sender_chain, window)
+            .expect("Failed to store chat window");
+
+        let nickname = self.chat_nickname(seat, nickname);
+        let entry = ChatEntry {
+            match_id,
+            sender_chain,
+            nickname,
+            seat,
+            text,
+            timestamp: now,
+        };
+        let chat_log = &mut self.state.chat_log;
+        chat_log.push_back(entry.clone());
+        if chat_log.count() > CHAT_LOG_CAPACITY {
+            chat_log.delete_front();
+        }
+        Ok(entry)
+    }
+
+    /// Send an emote from `sender_chain` to the table (not logged, only cooled down)
+    pub async fn handle_react_message(
+        &mut self,
+        sender_chain: ChainId,
+        nickname: Option<String>,
+        emote: Emote,
+        target_seat: Option<usize>,
+    ) -> LinotResponse {
+        match self.post_reaction(sender_chain, nickname, emote, target_seat).await {
+            Ok(reaction) => {
+                log::info!("PLAY_CHAIN: {} reacted {:?}", reaction.nickname, reaction.emote);
+                self.emit_event(GameEvent::Reaction { reaction });
+                LinotResponse::Ok
+            }
+            Err(error) => {
+                log::warn!("PLAY_CHAIN: Reaction rejected - {}", error);
+                LinotResponse::Error(error.to_string())
+            }
+        }
+    }
+
+    async fn post_reaction(
+        &mut self,
+        sender_chain: ChainId,
+        nickname: Option<String>,
+        emote: Emote,
+        target_seat: Option<usize>,
+    ) -> Result<Reaction, ChatError> {
+        let now = self.now();
+        let seat = self.chat_seat(sender_chain)?;
+        if let Some(target) = target_seat {
+            if self.state.match_data.get().players.get(target).and_then(|p| p.as_ref()).is_none() {
+                return Err(ChatError::EmptySeat(target));
+            }
+        }
+
+        let last = self.state.last_reactions.get(&sender_chain).await
+            .expect("Failed to load last reaction");
+        chat::check_emote_cooldown(last, now)?;
+        self.state.last_reactions.insert(&sender_chain, now)
+            .expect("Failed to store last reaction");
+
+        Ok(Reaction {
+            match_id: self.state.match_data.get().match_id,
+            sender_chain,
+            nickname: self.chat_nickname(seat, nickname),
+            seat,
+            emote,
+            target_seat,
+            timestamp: now,
+        })
+    }
+
+    /// Seat of a chat sender (None for a spectator); anyone else can't chat
+    fn chat_seat(&self, sender_chain: ChainId) -> Result<Option<usize>, ChatError> {
+        let match_data = self.state.match_data.get();
+        let seat = match_data.players.iter().position(|player| {
+            player.as_ref().is_some_and(|player| player.chain_id == sender_chain && !player.is_bot())
+        });
+        if seat.is_none() && !match_data.spectators.contains(&sender_chain) {
+            return Err(ChatError::NotAtTable);
+        }
+        Ok(seat)
+    }
+
+    /// Seated players go by their seat's nickname, spectators by the one they sent
+    fn chat_nickname(&self, seat: Option<usize>, nickname: Option<String>) -> String {
+        match seat {
+            Some(seat) => self.seat_nickname(seat),
+            None => nickname.and_then(|nickname| chat::clean_text(&nickname).ok())
+                .unwrap_or_else(|| "Spectator".to_string()),
+        }
+    }
+
+    // ================================================================================
+    // ESCROW (Entry fees of staked matches)
+    // ================================================================================
+
+    /// Whether the match on this chain still holds fees nobody has been paid
+    pub fn escrow_outstanding(&self) -> bool {
+        self.state.match_data.get().escrow.as_ref().is_some_and(|escrow| !escrow.settled)
+    }
+
+    /// Hold `stake` for the freshly created match, paid by the host from `host_account`
+    pub fn open_escrow(&mut self, stake: Stake, host_account: Account) {
+        let mut escrow = Escrow::new(stake);
+        escrow.deposit(0, host_account);
+        self.state.match_data.get_mut().escrow = Some(escrow);
+    }
+
+    /// Send back a fee that came with a request the PLAY_CHAIN turned down
+    pub fn refund_payment(&mut self, stake: Stake, account: Account) {
+        let chain_id = self.runtime.chain_id();
+        let source = self.escrow_account(chain_id);
+        if let Err(error) = self.ledger(stake.token).transfer(source, stake.entry_fee, account) {
+            log::error!("PLAY_CHAIN: Refund to {:?} failed - {}", account, error);
+            return;
+        }
+        log::info!("PLAY_CHAIN: Refunded entry fee of {} to {:?}", stake.entry_fee, account);
+    }
+
+    /// Pay the pot out once the match is finished - runs after every action
+    pub async fn settle_escrow(&mut self) {
+        let match_data = self.state.match_data.get();
+        if match_data.status != MatchStatus::Finished {
+            return;
+        }
+        let Some(escrow) = match_data.escrow.as_ref().filter(|escrow| !escrow.settled) else {
+            return;
+        };
+        let payouts = escrow.payouts(match_data);
+        let token = escrow.stake.token;
+        if let Some(escrow) = self.state.match_data.get_mut().escrow.as_mut() {
+            escrow.settled = true;
+        }
+
+        self.send_from_escrow(token, &payouts);
+        log::info!("PLAY_CHAIN: Pot paid out to {} seat(s)", payouts.len());
+        self.emit_event(GameEvent::PotPaidOut { payouts });
+    }
+
+    fn send_from_escrow(&mut self, token: ApplicationId, payouts: &[Payout]) {
+        let chain_id = self.runtime.chain_id();
+        let source = self.escrow_account(chain_id);
+        if let Err(error) = escrow::pay_out(&mut self.ledger(token), source, payouts) {
+            log::error!("PLAY_CHAIN: Escrow payout failed - {}", error);
         }
     }
 
@@ -765,93 +1209,50 @@ match_data.discard_pile.push(card);
     // HELPER FUNCTIONS
     // ================================================================================
 
+    /// Block time, or the deadline being settled
+    fn now(&mut self) -> u64 {
+        self.as_of.unwrap_or_else(|| self.runtime.system_time().micros())
+    }
+
     /// Centralized event emission
-    fn emit_event(&mut self, event: GameEvent) {
+    pub fn emit_event(&mut self, event: GameEvent) {
         self.runtime.emit(GAME_STREAM_NAME.into(), &event);
     }
 
-    /// Execute special card effects (actually modify game state)
-    /// `player_who_played_index` is the index of the player who played the special card
-    fn execute_special_effect(&mut self, effect: Option<SpecialEffect>, player_who_played_index: usize) {
-        if let Some(special) = effect {
-            match special {
-                SpecialEffect::PickTwo => {
-                    let match_data = self.state.match_data.get_mut();
-                    
-                    // Check if this is a stacking situation
-                    if match_data.pending_draw_type == Some(2) {
-                        // Another Pick Two played - stack it!
-                        match_data.pending_draw_stack += 2;
-                        log::info!("PLAY_CHAIN: Pick Two stacked - total pending: {}", match_data.pending_draw_stack);
-                        // Next player can also stack or must draw
-                        return;
-                    }
-                    
-                    // Set penalty - next player must handle this
-                    match_data.pending_draw_stack = 2;
-                    match_data.pending_draw_type = Some(2);
-                    log::info!("PLAY_CHAIN: Pick Two played - pending draw: 2 (next player can block or must draw)");
-                }
-                
-                SpecialEffect::PickThree => {
-                    let match_data = self.state.match_data.get_mut();
-                    
-                    // Check if this is a stacking situation
-                    if match_data.pending_draw_type == Some(5) {
-                        // Another Pick Three played - stack it!
-                        match_data.pending_draw_stack += 3;
-                        log::info!("PLAY_CHAIN: Pick Three stacked - total pending: {}", match_data.pending_draw_stack);
-                        return;
-                    }
-                    
-                    // Set penalty - next player must handle this
-                    match_data.pending_draw_stack = 3;
-                    match_data.pending_draw_type = Some(5);
-                    log::info!("PLAY_CHAIN: Pick Three played - pending draw: 3 (next player can block or must draw)");
-                }
-                
-                SpecialEffect::HoldOn => {
-                    let match_data = self.state.match_data.get_mut();
-                    let current_next = match_data.current_player_index;
-                    
-                    // Skip next player's turn
-                    let mut skip_next = (current_next + 1) % match_data.players.len();
-                    while match_data.players[skip_next].is_none() {
-                        skip_next = (skip_next + 1) % match_data.players.len();
-                    }
-                    match_data.current_player_index = skip_next;
-                    
-                    log::info!("PLAY_CHAIN: Hold On - next player skipped");
-                }
-                
-                SpecialEffect::GeneralMarket => {
-                    let match_data = self.state.match_data.get_mut();
-                    
-                    // Draw for all OTHER players (not the one who played the 14)
-                    for (index, player_opt) in match_data.players.iter_mut().enumerate() {
-                        if index == player_who_played_index {
-                            continue; // Skip the player who played General Market
-                        }
-                        
-                        if let Some(player) = player_opt {
-                            if let Some(card) = match_data.deck.pop() {
-                                player.hand.push(card);
-                                player.update_hand_size();
-                            }
-                        }
-                    }
-                    match_data.deck_size = match_data.deck.len();
-                    
-                    log::info!("PLAY_CHAIN: General Market - all OTHER players (not player {}) draw 1 card", player_who_played_index);
-                }
-                
-                SpecialEffect::WhotPlayed { .. } => {
-                    // No additional action - suit change already handled in validation
-                    log::info!("PLAY_CHAIN: Whot played - suit changed");
-                }
-            }
-        }
+    /// Append an accepted action to the current match's move log
+    async fn record_move(&mut self, mut record: MoveRecord) {
+        let match_data = self.state.match_data.get();
+        let match_id = match_data.match_id;
+        record.pending_draw_stack = match_data.pending_total();
+
+        self.state.move_logs.load_entry_mut(&match_id).await
+            .expect("Failed to load move log")
+            .push(record);
     }
 
-}
+    /// Number of moves recorded for the current match
+    async fn move_count(&mut self) -> u64 {
+        let match_id = self.state.match_data.get().match_id;
+        self.state.move_logs.load_entry_mut(&match_id).await
+            .expect("Failed to load move log")
+            .count() as u64
+    }
+
+    /// Nickname of the player in `seat` (empty for an empty seat)
+    fn seat_nickname(&self, seat: usize) -> String {
+        self.state.match_data.get().players.get(seat)
+            .and_then(|p| p.as_ref())
+            .map(|player| player.nickname.clone())
+            .unwrap_or_default()
+    }
 
+    /// Id for the next match hosted on this PLAY_CHAIN
+    pub fn next_match_id(&self) -> u64 {
+        let current = self.state.match_data.get();
+        if current.players.is_empty() {
+            current.match_id
+        } else {
+            current.match_id + 1
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use linera_sdk::abis::fungible::{Account, FungibleOperation, FungibleResponse, FungibleTokenAbi};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId};
+use linera_sdk::ContractRuntime;
+use linot::escrow::{EscrowError, TokenLedger};
+
+use super::super::LinotContract;
+
+/// Fungible-token application moving tokens out of accounts on the current chain
+pub struct FungibleLedger<'a> {
+    pub runtime: &'a mut ContractRuntime<LinotContract>,
+    pub token: ApplicationId,
+}
+
+impl TokenLedger for FungibleLedger<'_> {
+    fn transfer(&mut self, source: Account, amount: Amount, target: Account) -> Result<(), EscrowError> {
+        // The token application fails the whole block when the source can't cover it,
+        // anything but a plain `Ok` means the transfer didn't go through
+        let call = FungibleOperation::Transfer {
+            owner: source.owner,
+            amount,
+            target_account: target,
+        };
+        match self.runtime.call_application(true, self.token.with_abi::<FungibleTokenAbi>(), &call) {
+            FungibleResponse::Ok => Ok(()),
+            _ => Err(EscrowError::TransferRejected),
+        }
+    }
+}
+
+impl LinotContract {
+    /// Account of this application on `chain_id` - entry fees are held there
+    pub fn escrow_account(&mut self, chain_id: ChainId) -> Account {
+        Account {
+            chain_id,
+            owner: AccountOwner::from(self.runtime.application_id()),
+        }
+    }
+
+    /// Ledger for `token` on the current chain
+    pub fn ledger(&mut self, token: ApplicationId) -> FungibleLedger<'_> {
+        FungibleLedger {
+            runtime: &mut self.runtime,
+            token,
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationPermissions, ChainId, StreamUpdate};
+use linot::tournament::{RoundProgress, Tournament, TournamentSettings, TournamentStatus};
+use linot::{GameEvent, LinotResponse, Message, Player, GAME_STREAM_NAME};
+
+use super::super::LinotContract;
+
+impl LinotContract {
+    // ================================================================================
+    // TOURNAMENT_CHAIN OPERATIONS (Coordinator of a tournament)
+    // ================================================================================
+
+    /// Make this chain the coordinator of a new tournament
+    pub async fn handle_create_tournament(&mut self, name: String, settings: TournamentSettings) -> LinotResponse {
+        let organizer = match self.runtime.authenticated_signer() {
+            Some(signer) => signer,
+            None => return LinotResponse::Error("Organizer must be authenticated".to_string()),
+        };
+        if self.state.tournament.get().as_ref().is_some_and(|tournament| tournament.status != TournamentStatus::Finished) {
+            return LinotResponse::Error("A tournament is already running on this chain".to_string());
+        }
+        // Table results arrive as game events, so the coordinator can't follow a match of its own
+        if !self.state.match_data.get().players.is_empty()
+            || self.state.subscribed_play_chain.get().is_some()
+            || self.state.spectated_play_chain.get().is_some()
+        {
+            return LinotResponse::Error("Coordinator chain must not host or follow a match".to_string());
+        }
+
+        let tournament = match Tournament::new(name.clone(), organizer, settings) {
+            Ok(tournament) => tournament,
+            Err(error) => return LinotResponse::Error(error.to_string()),
+        };
+        self.state.tournament.set(Some(tournament));
+
+        log::info!("TOURNAMENT_CHAIN: Tournament '{}' open for registration ({:?})", name, settings.format);
+        LinotResponse::Ok
+    }
+
+    /// Close registration and seat the first round (organizer only)
+    pub async fn handle_start_tournament(&mut self, owner: Option<AccountOwner>) -> LinotResponse {
+        let Some(tournament) = self.state.tournament.get_mut().as_mut() else {
+            return LinotResponse::Error("No tournament on this chain".to_string());
+        };
+        let Some(owner) = owner else {
+            return LinotResponse::Error("Signer required".to_string());
+        };
+
+        let round = match tournament.start(&owner) {
+            Ok(round) => round,
+            Err(error) => {
+                log::warn!("TOURNAMENT_CHAIN: StartTournament rejected - {}", error);
+                return LinotResponse::Error(error.to_string());
+            }
+        };
+
+        log::info!("TOURNAMENT_CHAIN: Tournament started with {} entrants", tournament.entrants.len());
+        self.begin_round(round);
+        LinotResponse::Ok
+    }
+
+    // ================================================================================
+    // MESSAGE HANDLERS
+    // ================================================================================
+
+    /// USER_CHAIN: ask the coordinator for a place in the tournament
+    pub async fn handle_register_for_tournament(&mut self, tournament_chain_id: ChainId, nickname: String) {
+        let owner = self.runtime.authenticated_signer()
+            .expect("Signer required");
+        let message = Message::RegisterEntrant {
+            owner,
+            player_chain: self.runtime.chain_id(),
+            nickname: nickname.clone(),
+        };
+        self.runtime.prepare_message(message).send_to(tournament_chain_id);
+
+        self.state.tournament_chain.set(Some(tournament_chain_id));
+        self.state.player_nickname.set(Some(nickname));
+        log::info!("USER_CHAIN: Sent tournament registration to {:?}", tournament_chain_id);
+    }
+
+    /// TOURNAMENT_CHAIN: sign up a player while registration is open
+    pub async fn handle_register_entrant_message(&mut self, owner: AccountOwner, player_chain: ChainId, nickname: String) {
+        let tournament_chain_id = self.runtime.chain_id();
+        let registered = match self.state.tournament.get_mut().as_mut() {
+            Some(tournament) => tournament.register(owner, player_chain, nickname.clone())
+                .map(|_| tournament.entrants.len())
+                .map_err(|error| error.to_string()),
+            None => Err("No tournament on this chain".to_string()),
+        };
+
+        let result = Message::RegistrationResult {
+            tournament_chain_id,
+            success: registered.is_ok(),
+        };
+        self.runtime.prepare_message(result).send_to(player_chain);
+
+        match registered {
+            Ok(entrant_count) => {
+                log::info!("TOURNAMENT_CHAIN: {} registered ({} entrants)", nickname, entrant_count);
+                self.emit_event(GameEvent::EntrantRegistered { nickname, entrant_count });
+            }
+            Err(error) => log::warn!("TOURNAMENT_CHAIN: Registration rejected - {}", error),
+        }
+    }
+
+    /// USER_CHAIN: registration answer from the coordinator
+    pub async fn handle_registration_result(&mut self, tournament_chain_id: ChainId, success: bool) {
+        if !success {
+            log::warn!("USER_CHAIN: Tournament {:?} rejected the registration", tournament_chain_id);
+            if *self.state.tournament_chain.get() == Some(tournament_chain_id) {
+                self.state.tournament_chain.set(None);
+            }
+            return;
+        }
+        log::info!("USER_CHAIN: Registered for tournament {:?}", tournament_chain_id);
+    }
+
+    /// TOURNAMENT_CHAIN: collect `MatchEnded` from the table chains and advance the bracket
+    pub async fn handle_tournament_streams(&mut self, updates: Vec<StreamUpdate>) {
+        let app_id = self.runtime.application_id().forget_abi();
+        for update in updates {
+            for index in update.new_indices() {
+                let event: GameEvent = self.runtime.read_event(
+                    update.chain_id,
+                    GAME_STREAM_NAME.into(),
+                    index
+                );
+                let GameEvent::MatchEnded { winner_index, .. } = event else {
+                    continue;
+                };
+
+                // A drawn match reports `usize::MAX` as its winner
+                let winner_seat = (winner_index != usize::MAX).then_some(winner_index);
+                let Some(tournament) = self.state.tournament.get_mut().as_mut() else {
+                    return;
+                };
+                let progress = match tournament.record_result(update.chain_id, winner_seat) {
+                    Ok(progress) => progress,
+                    Err(error) => {
+                        log::warn!("TOURNAMENT_CHAIN: Result from {:?} ignored - {}", update.chain_id, error);
+                        continue;
+                    }
+                };
+                self.runtime.unsubscribe_from_events(update.chain_id, app_id, GAME_STREAM_NAME.into());
+
+                match progress {
+                    RoundProgress::Waiting => {
+                        log::info!("TOURNAMENT_CHAIN: Table on {:?} finished", update.chain_id);
+                    }
+                    RoundProgress::NextRound(round) => self.begin_round(round),
+                    RoundProgress::Finished { champion } => {
+                        let champion = champion.and_then(|index| {
+                            self.state.tournament.get().as_ref()
+                                .map(|tournament| tournament.entrants[index].nickname.clone())
+                        });
+                        log::info!("TOURNAMENT_CHAIN: Tournament finished, champion: {:?}", champion);
+                        self.emit_event(GameEvent::TournamentFinished { champion });
+                    }
+                }
+            }
+        }
+    }
+
+    // ================================================================================
+    // INTERNAL HELPERS
+    // ================================================================================
+
+    /// Announce a freshly seated round and open a PLAY_CHAIN for each of its tables
+    fn begin_round(&mut self, round: u8) {
+        let Some(tournament) = self.state.tournament.get().clone() else {
+            return;
+        };
+        let Some(current) = tournament.current_round() else {
+            return;
+        };
+        self.emit_event(GameEvent::TournamentRoundStarted {
+            round,
+            table_count: current.tables.len(),
+        });
+
+        let tournament_chain_id = self.runtime.chain_id();
+        let app_id = self.runtime.application_id().forget_abi();
+        for table in tournament.tables_to_open() {
+            let players: Vec<Player> = current.tables[table].entrants.iter()
+                .map(|&index| {
+                    let entrant = &tournament.entrants[index];
+                    Player::new(entrant.chain_id, entrant.owner, entrant.nickname.clone())
+                })
+                .collect();
+
+            // Each table is a chain of its own, owned like the coordinator
+            let ownership = self.runtime.chain_ownership();
+            let play_chain_id = self.runtime.open_chain(ownership, ApplicationPermissions::default(), Amount::ZERO);
+            self.runtime.subscribe_to_events(play_chain_id, app_id, GAME_STREAM_NAME.into());
+            self.runtime.prepare_message(Message::OpenTable {
+                tournament_chain_id,
+                round,
+                table,
+                players,
+                time_control: tournament.settings.time_control,
+                rules: tournament.settings.rules,
+            }).send_to(play_chain_id);
+
+            if let Some(tournament) = self.state.tournament.get_mut().as_mut() {
+                tournament.assign_table(table, play_chain_id);
+            }
+            log::info!("TOURNAMENT_CHAIN: Round {} table {} opened on {:?}", round, table, play_chain_id);
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use linot::UserStatus;
-use linera_sdk::linera_base_types::{ChainId, StreamUpdate};
+use linera_sdk::abis::fungible::Account;
+use linera_sdk::linera_base_types::{AccountOwner, ChainId, StreamUpdate};
+use linot::chat::{self, Reaction};
+use linot::escrow::{Stake, TokenLedger};
 use linot::{Card, GameEvent, MatchData, Message, GAME_STREAM_NAME};
 
 use super::super::LinotContract;
@@ -29,13 +32,32 @@ impl LinotContract {
         log::info!("USER_CHAIN: Unsubscribed from play chain: {:?}", play_chain_id);
     }
 
-    /// Send join request to PLAY_CHAIN
-    pub async fn handle_join_match(&mut self, play_chain_id: ChainId, nickname: String) {
+    /// Pay `stake` from `payer` into the escrow on `play_chain_id`
+    pub fn pay_entry_fee(&mut self, payer: AccountOwner, play_chain_id: ChainId, stake: Stake) {
+        let source = Account {
+            chain_id: self.runtime.chain_id(),
+            owner: payer,
+        };
+        let target = self.escrow_account(play_chain_id);
+        self.ledger(stake.token)
+            .transfer(source, stake.entry_fee, target)
+            .expect("Failed to pay the entry fee");
+        log::info!("USER_CHAIN: Paid entry fee of {} into the escrow on {:?}", stake.entry_fee, play_chain_id);
+    }
+
+    /// Send join request to PLAY_CHAIN, paying the entry fee first for a staked match
+    pub async fn handle_join_match(&mut self, play_chain_id: ChainId, nickname: String, stake: Option<Stake>) {
+        let player_owner = self.runtime.authenticated_signer()
+            .expect("Signer required");
+        if let Some(stake) = stake {
+            self.pay_entry_fee(player_owner, play_chain_id, stake);
+        }
+
         let message = Message::RequestJoin {
-            player_owner: self.runtime.authenticated_signer()
-                .expect("Signer required"),
+            player_owner,
             player_chain: self.runtime.chain_id(),
             nickname: nickname.clone(),
+            stake,
         };
 
         self.runtime.prepare_message(message).send_to(play_chain_id);
@@ -71,6 +93,67 @@ impl LinotContract {
         log::info!("USER_CHAIN: ✅ Subscribed to PLAY_CHAIN {:?} after join confirmation", play_chain_id);
     }
 
+    /// Ask a PLAY_CHAIN to let us watch without a seat
+    pub async fn handle_spectate(&mut self, play_chain_id: ChainId) {
+        let message = Message::RequestSpectate {
+            spectator_chain: self.runtime.chain_id(),
+        };
+        self.runtime.prepare_message(message).send_to(play_chain_id);
+
+        // Subscribe once PLAY_CHAIN confirms, same as joining
+        self.state.user_status.set(UserStatus::WaitingToJoin);
+
+        log::info!("USER_CHAIN: Sent spectate request to play chain: {:?}", play_chain_id);
+    }
+
+    /// Handle spectate confirmation from PLAY_CHAIN
+    pub async fn handle_spectate_confirmed(&mut self, play_chain_id: ChainId, snapshot: Option<Box<MatchData>>) {
+        let Some(snapshot) = snapshot else {
+            log::warn!("USER_CHAIN: Spectate was rejected by PLAY_CHAIN");
+            self.state.user_status.set(UserStatus::Idle);
+            return;
+        };
+
+        let app_id = self.runtime.application_id().forget_abi();
+        self.runtime.subscribe_to_events(
+            play_chain_id,
+            app_id,
+            GAME_STREAM_NAME.into()
+        );
+
+        // PLAY_CHAIN already stripped private data, strip again in case of an older PLAY_CHAIN
+        self.state.local_match.set(Some(snapshot.public_view()));
+        self.state.spectated_play_chain.set(Some(play_chain_id));
+        self.state.user_status.set(UserStatus::Spectating);
+
+        log::info!("USER_CHAIN: Spectating PLAY_CHAIN {:?}", play_chain_id);
+    }
+
+    /// Stop watching the spectated PLAY_CHAIN
+    pub async fn handle_stop_spectating(&mut self) {
+        let Some(play_chain_id) = *self.state.spectated_play_chain.get() else {
+            return;
+        };
+
+        let app_id = self.runtime.application_id().forget_abi();
+        self.runtime.unsubscribe_from_events(
+            play_chain_id,
+            app_id,
+            GAME_STREAM_NAME.into()
+        );
+
+        let message = Message::StopSpectatingAction {
+            spectator_chain: self.runtime.chain_id(),
+        };
+        self.runtime.prepare_message(message).send_to(play_chain_id);
+
+        self.state.spectated_play_chain.set(None);
+        self.state.local_match.set(None);
+        self.state.user_status.set(UserStatus::Idle);
+
+        log::info!("USER_CHAIN: Stopped spectating play chain: {:?}", play_chain_id);
+    }
+
     /// Process event streams from PLAY_CHAIN - THIS IS THE CRITICAL SYNC LOGIC
     pub async fn handle_process_streams(&mut self, updates: Vec<StreamUpdate>) {
         for update in updates {
@@ -95,12 +178,36 @@ impl LinotContract {
                         self.handle_player_joined_event(nickname, player_count);
                     }
                     
+                    GameEvent::PlayerLeftLobby { nickname, player_count } => {
+                        self.handle_player_left_lobby_event(nickname, player_count);
+                    }
+                    
+                    GameEvent::TeamsAssigned { teams } => {
+                        self.handle_teams_assigned_event(teams);
+                    }
+                    
                     GameEvent::MatchStarted { players, first_player, top_card } => {
                         self.handle_match_started_event(players, first_player, top_card);
                     }
                     
-                    GameEvent::CardPlayed { player_nickname, card, next_player, special_effect } => {
-                        self.handle_card_played_event(player_nickname, card, next_player, special_effect);
+                    GameEvent::CardPlayed { player_nickname, card, next_player, special_effect, whot_demand } => {
+                        self.handle_card_played_event(player_nickname, card, next_player, special_effect, whot_demand);
+                    }
+                    
+                    GameEvent::CardsPlayed { player_nickname, cards, next_player, special_effect, whot_demand } => {
+                        self.handle_cards_played_event(player_nickname, cards, next_player, special_effect, whot_demand);
+                    }
+                    
+                    GameEvent::GeneralMarketResolved { player_nickname, draws } => {
+                        for draw in &draws {
+                            log::info!(
+                                "USER_CHAIN: General Market from {}: {} drew {}{}",
+                                player_nickname,
+                                draw.player_nickname,
+                                draw.cards_drawn,
+                                if draw.defended { " (defended)" } else { "" },
+                            );
+                        }
                     }
                     
                     GameEvent::CardsDrawn { player_nickname, count, next_player } => {
@@ -111,32 +218,89 @@ impl LinotContract {
                         self.handle_last_card_called_event(player_nickname);
                     }
                     
-                    GameEvent::MatchWon { winner_nickname, winner_index } => {
-                        self.handle_match_ended_event(winner_nickname, winner_index);
+                    GameEvent::LastCardRejected { player_nickname, hand_size, penalty_cards } => {
+                        log::info!("USER_CHAIN: {} called last card holding {} cards, drew {}", player_nickname, hand_size, penalty_cards);
+                    }
+                    
+                    GameEvent::HandAnnounced { player_nickname, announcement } => {
+                        log::info!("USER_CHAIN: {} - {:?}", player_nickname, announcement);
+                    }
+                    
+                    GameEvent::MatchWon { winner_nickname, winner_index, winning_team } => {
+                        self.handle_match_ended_event(winner_nickname, winner_index, winning_team);
                     }
                     
                     // NOTE: Events are lightweight notifications only!
                     // Frontend should query PLAY_CHAIN for full match state
                     // when events arrive, not rely on event data
                     
+                    GameEvent::MatchCancelled { host } => {
+                        self.handle_match_cancelled_event(host);
+                    }
+                    
+                    GameEvent::PotPaidOut { payouts } => {
+                        for payout in &payouts {
+                            log::info!("USER_CHAIN: Seat {} won {} from the pot", payout.seat, payout.amount);
+                        }
+                    }
+                    
+                    GameEvent::EntryFeesRefunded { refunds } => {
+                        log::info!("USER_CHAIN: {} entry fees refunded", refunds.len());
+                    }
+                    
                     GameEvent::TurnStarted { player_nickname, duration_micros } => {
                         log::info!("USER_CHAIN: Turn started for {}, duration: {}µs", player_nickname, duration_micros);
+                        if let Some(local_match) = self.state.local_match.get_mut() {
+                            local_match.current_turn_allotment = duration_micros;
+                        }
                     }
                     
                     GameEvent::TurnWarning { player_nickname, time_left_micros } => {
                         log::info!("USER_CHAIN: Turn warning for {}, {}µs remaining", player_nickname, time_left_micros);
                     }
                     
-                    GameEvent::TurnTimeout { player_nickname, auto_drawn } => {
-                        log::info!("USER_CHAIN: {} timed out, auto-draw: {}", player_nickname, auto_drawn);
+                    GameEvent::TurnTimeout { player_nickname, cards_drawn, penalty_served } => {
+                        log::info!("USER_CHAIN: {} timed out, drew {} ({} penalty)", player_nickname, cards_drawn, penalty_served);
+                    }
+                    
+                    GameEvent::MatchEnded { winner, winner_index, winning_team } => {
+                        self.handle_match_ended_event(winner, winner_index, winning_team);
+                    }
+                    
+                    GameEvent::ChallengeResolved { challenger, challenged, success, penalty } => {
+                        self.handle_challenge_resolved_event(challenger, challenged, success, penalty);
+                    }
+                    
+                    GameEvent::PlayerRemovedForInactivity { player_nickname, replaced_by_bot, next_player } => {
+                        self.handle_player_removed_for_inactivity_event(player_nickname, replaced_by_bot, next_player);
                     }
                     
-                    GameEvent::MatchEnded { winner, winner_index } => {
-                        self.handle_match_ended_event(winner, winner_index);
+                    GameEvent::PlayerOutOfTime { player_nickname, next_player } => {
+                        self.handle_player_out_of_time_event(player_nickname, next_player);
                     }
                     
-                    GameEvent::ChallengePenalty { challenged_player, penalty_cards } => {
-                        self.handle_challenge_penalty_event(challenged_player, penalty_cards);
+                    GameEvent::SpectatorCountChanged { spectator_count } => {
+                        self.handle_spectator_count_changed_event(spectator_count);
+                    }
+                    
+                    GameEvent::ChatMessage { entry } => {
+                        log::info!("USER_CHAIN: Chat from {}: {}", entry.nickname, entry.text);
+                    }
+                    
+                    GameEvent::Reaction { reaction } => {
+                        self.handle_reaction_event(reaction);
+                    }
+                    
+                    GameEvent::EntrantRegistered { nickname, entrant_count } => {
+                        log::info!("USER_CHAIN: {} registered for the tournament ({} entrants)", nickname, entrant_count);
+                    }
+                    
+                    GameEvent::TournamentRoundStarted { round, table_count } => {
+                        log::info!("USER_CHAIN: Tournament round {} started on {} tables", round, table_count);
+                    }
+                    
+                    GameEvent::TournamentFinished { champion } => {
+                        log::info!("USER_CHAIN: Tournament finished, champion: {:?}", champion);
                     }
                 }
             }
@@ -168,6 +332,13 @@ impl LinotContract {
         }
     }
 
+    fn handle_player_left_lobby_event(&mut self, nickname: String, player_count: usize) {
+        if let Some(local_match) = self.state.local_match.get_mut() {
+            log::info!("USER_CHAIN: Player {} left the lobby (total: {})", nickname, player_count);
+            local_match.deck_size = player_count; // Same player count tracking as PlayerJoined
+        }
+    }
+
     fn handle_match_started_event(&mut self, players: Vec<String>, first_player: String, top_card: Card) {
         if let Some(mut match_data) = self.state.local_match.get().clone() {
             match_data.status = linot::MatchStatus::InProgress;
@@ -183,10 +354,32 @@ impl LinotContract {
         }
     }
 
-    fn handle_card_played_event(&mut self, player_nickname: String, card: Card, next_player: String, special_effect: Option<linot::SpecialEffect>) {
+    fn handle_card_played_event(
+        &mut self,
+        player_nickname: String,
+        card: Card,
+        next_player: String,
+        special_effect: Option<linot::SpecialEffect>,
+        whot_demand: Option<linot::WhotDemand>,
+    ) {
+        self.handle_cards_played_event(player_nickname, vec![card], next_player, special_effect, whot_demand);
+    }
+
+    fn handle_cards_played_event(
+        &mut self,
+        player_nickname: String,
+        cards: Vec<Card>,
+        next_player: String,
+        special_effect: Option<linot::SpecialEffect>,
+        whot_demand: Option<linot::WhotDemand>,
+    ) {
         if let Some(mut match_data) = self.state.local_match.get().clone() {
-            // Add card to discard pile
-            match_data.discard_pile.push(card.clone());
+            // Add cards to discard pile - the last one is the new top card
+            match_data.discard_pile.extend(cards.iter().cloned());
+            match_data.whot_demand = whot_demand;
+            if special_effect == Some(linot::SpecialEffect::Reverse) && cards.len() % 2 == 1 {
+                match_data.direction = match_data.direction.reversed();
+            }
             
             // Update current player index by finding next_player
             for (index, player_opt) in match_data.players.iter().enumerate() {
@@ -199,10 +392,10 @@ impl LinotContract {
             }
             
             self.state.local_match.set(Some(match_data));
-            log::info!("USER_CHAIN: {} played {:?}, next: {}", player_nickname, card, next_player);
+            log::info!("USER_CHAIN: {} played {:?}, next: {}", player_nickname, cards, next_player);
             
             if let Some(effect) = special_effect {
-                log::info!("USER_CHAIN: Special effect: {:?}", effect);
+                log::info!("USER_CHAIN: Special effect: {:?} x{}", effect, cards.len());
             }
         }
     }
@@ -241,26 +434,52 @@ impl LinotContract {
         }
     }
 
-    fn handle_match_ended_event(&mut self, winner: String, winner_index: usize) {
+    fn handle_teams_assigned_event(&mut self, teams: Vec<u8>) {
+        if let Some(mut match_data) = self.state.local_match.get().clone() {
+            log::info!("USER_CHAIN: Teams assigned: {:?}", teams);
+            match_data.teams = teams;
+            
+            self.state.local_match.set(Some(match_data));
+        }
+    }
+
+    fn handle_match_ended_event(&mut self, winner: String, winner_index: usize, winning_team: Option<u8>) {
         if let Some(mut match_data) = self.state.local_match.get().clone() {
             match_data.status = linot::MatchStatus::Finished;
             match_data.winner_index = Some(winner_index);
+            match_data.winning_team = winning_team;
+            
+            self.state.local_match.set(Some(match_data));
+            self.state.user_status.set(UserStatus::Idle);
+            
+            match winning_team {
+                Some(team) => log::info!("USER_CHAIN: Match ended, team {} won ({})", team, winner),
+                None => log::info!("USER_CHAIN: Match ended, winner: {}", winner),
+            }
+        }
+    }
+
+    fn handle_match_cancelled_event(&mut self, host: String) {
+        if let Some(mut match_data) = self.state.local_match.get().clone() {
+            match_data.status = linot::MatchStatus::Finished;
             
             self.state.local_match.set(Some(match_data));
             self.state.user_status.set(UserStatus::Idle);
             
-            log::info!("USER_CHAIN: Match ended, winner: {}", winner);
+            log::info!("USER_CHAIN: Match called off by {}", host);
         }
     }
 
-    fn handle_challenge_penalty_event(&mut self, challenged_player: String, penalty_cards: u8) {
+    fn handle_challenge_resolved_event(&mut self, challenger: String, challenged: String, success: bool, penalty: u8) {
         if let Some(mut match_data) = self.state.local_match.get().clone() {
-            // Find player and update their hand size
+            // The penalty goes to the challenged player on success, to the challenger otherwise
+            let penalized = if success { &challenged } else { &challenger };
             for player_opt in match_data.players.iter_mut() {
                 if let Some(player) = player_opt {
-                    if player.nickname == challenged_player {
-                        player.hand_size += penalty_cards as usize;
-                        log::info!("USER_CHAIN: {} penalized {} cards", challenged_player, penalty_cards);
+                    if &player.nickname == penalized {
+                        player.hand_size += penalty as usize;
+                        log::info!("USER_CHAIN: {} challenged {} ({}) - {} penalized {} cards",
+                            challenger, challenged, if success { "upheld" } else { "failed" }, penalized, penalty);
                         break;
                     }
                 }
@@ -268,4 +487,55 @@ impl LinotContract {
             self.state.local_match.set(Some(match_data));
         }
     }
+
+    fn handle_player_removed_for_inactivity_event(&mut self, player_nickname: String, replaced_by_bot: bool, next_player: String) {
+        if let Some(mut match_data) = self.state.local_match.get().clone() {
+            for (index, player_opt) in match_data.players.iter_mut().enumerate() {
+                if let Some(player) = player_opt {
+                    if player.nickname == player_nickname {
+                        if replaced_by_bot {
+                            player.bot = Some(linot::game::INACTIVITY_BOT);
+                        } else {
+                            player.forfeited = true;
+                        }
+                    }
+                    if player.nickname == next_player {
+                        match_data.current_player_index = index;
+                    }
+                }
+            }
+            self.state.local_match.set(Some(match_data));
+            log::info!("USER_CHAIN: {} removed for inactivity (bot: {})", player_nickname, replaced_by_bot);
+        }
+    }
+
+    fn handle_player_out_of_time_event(&mut self, player_nickname: String, next_player: String) {
+        if let Some(mut match_data) = self.state.local_match.get().clone() {
+            for (index, player_opt) in match_data.players.iter_mut().enumerate() {
+                if let Some(player) = player_opt {
+                    if player.nickname == player_nickname {
+                        player.forfeited = true;
+                        player.time_bank = Some(0);
+                    }
+                    if player.nickname == next_player {
+                        match_data.current_player_index = index;
+                    }
+                }
+            }
+            self.state.local_match.set(Some(match_data));
+            log::info!("USER_CHAIN: {} ran out of time, next: {}", player_nickname, next_player);
+        }
+    }
+
+    fn handle_reaction_event(&mut self, reaction: Reaction) {
+        log::info!("USER_CHAIN: {} reacted {:?}", reaction.nickname, reaction.emote);
+        chat::keep_recent(self.state.recent_reactions.get_mut(), reaction);
+    }
+
+    fn handle_spectator_count_changed_event(&mut self, spectator_count: usize) {
+        if let Some(mut match_data) = self.state.local_match.get().clone() {
+            match_data.spectator_count = spectator_count;
+            self.state.local_match.set(Some(match_data));
+        }
+    }
 }
@@ -0,0 +1,251 @@
+//! Table chat and reactions.
+//!
+//! Seated players and spectators post short messages to the PLAY_CHAIN, which
+//! keeps the latest ones in a ring buffer and broadcasts each one as a
+//! `ChatMessage` event. Every sender chain gets a burst of messages per window,
+//! and the host can mute a chain for the rest of the match.
+//!
+//! Reactions are the safe alternative for public tables: a fixed set of emotes,
+//! broadcast as a `Reaction` event without being logged. They only have a
+//! cool-down, so muted chains can still react.
+
+use async_graphql::{Enum, SimpleObject};
+use linera_sdk::linera_base_types::ChainId;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Longest message, in characters
+pub const MAX_CHAT_LENGTH: usize = 280;
+
+/// Most messages returned by a history query
+pub const CHAT_HISTORY_LIMIT: usize = 100;
+
+/// Messages a PLAY_CHAIN keeps, across all its matches - the oldest are dropped first
+pub const CHAT_LOG_CAPACITY: usize = 500;
+
+/// Messages one chain may send per window
+pub const CHAT_BURST: usize = 5;
+
+/// Length of the rate-limit window: 10 seconds
+pub const CHAT_WINDOW_MICROS: u64 = 10_000_000;
+
+/// Gap a chain must leave between two reactions: 3 seconds
+pub const EMOTE_COOLDOWN_MICROS: u64 = 3_000_000;
+
+/// How long a USER_CHAIN keeps a reaction around: 10 seconds
+pub const REACTION_TTL_MICROS: u64 = 10_000_000;
+
+/// Most reactions a USER_CHAIN keeps at once
+pub const MAX_RECENT_REACTIONS: usize = 10;
+
+/// Why a chat message or reaction was rejected
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ChatError {
+    #[error("Message is empty")]
+    Empty,
+    #[error("Message is too long. Max: {max} characters")]
+    TooLong { max: usize },
+    #[error("Sending too fast. Try again in {retry_in_micros}µs")]
+    RateLimited { retry_in_micros: u64 },
+    #[error("You are muted at this table")]
+    Muted,
+    #[error("Only players and spectators of this table can chat")]
+    NotAtTable,
+    #[error("Reacting too fast. Try again in {retry_in_micros}µs")]
+    EmoteCooldown { retry_in_micros: u64 },
+    #[error("No player in seat {0}")]
+    EmptySeat(usize),
+}
+
+/// Predefined reactions
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum Emote {
+    NicePlay,
+    WellPlayed,
+    HurryUp,
+    Laughing,
+    Oops,
+    Thinking,
+    GoodLuck,
+    Wow,
+}
+
+/// One message of the table chat
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct ChatEntry {
+    pub match_id: u64,
+    pub sender_chain: ChainId,   // Sender's USER_CHAIN - what the host mutes
+    pub nickname: String,
+    pub seat: Option<usize>,     // None for a spectator
+    pub text: String,
+    pub timestamp: u64,
+}
+
+/// Emote sent to the table, or at one seat in particular
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Reaction {
+    pub match_id: u64,
+    pub sender_chain: ChainId,
+    pub nickname: String,
+    pub seat: Option<usize>,         // None for a spectator
+    pub emote: Emote,
+    pub target_seat: Option<usize>,  // None for the whole table
+    pub timestamp: u64,
+}
+
+/// Reject a reaction sent at `now` less than the cool-down after the chain's `last` one
+pub fn check_emote_cooldown(last: Option<u64>, now: u64) -> Result<(), ChatError> {
+    match last {
+        Some(last) if now.saturating_sub(last) < EMOTE_COOLDOWN_MICROS => Err(ChatError::EmoteCooldown {
+            retry_in_micros: last + EMOTE_COOLDOWN_MICROS - now,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Add `reaction` to a USER_CHAIN's recent reactions, dropping the expired and the oldest
+pub fn keep_recent(recent: &mut Vec<Reaction>, reaction: Reaction) {
+    let now = reaction.timestamp;
+    recent.retain(|kept| now.saturating_sub(kept.timestamp) < REACTION_TTL_MICROS);
+    recent.push(reaction);
+    if recent.len() > MAX_RECENT_REACTIONS {
+        recent.drain(..recent.len() - MAX_RECENT_REACTIONS);
+    }
+}
+
+/// Trim `text` and drop control characters, rejecting empty or over-long messages
+pub fn clean_text(text: &str) -> Result<String, ChatError> {
+    let cleaned: String = text.trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    if cleaned.is_empty() {
+        return Err(ChatError::Empty);
+    }
+    if cleaned.chars().count() > MAX_CHAT_LENGTH {
+        return Err(ChatError::TooLong { max: MAX_CHAT_LENGTH });
+    }
+    Ok(cleaned)
+}
+
+/// Recent send times of one chain
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RateWindow {
+    pub sent: Vec<u64>,
+}
+
+impl RateWindow {
+    /// Count a message sent at `now`, unless the chain used up its burst for the window
+    pub fn try_send(&mut self, now: u64) -> Result<(), ChatError> {
+        self.sent.retain(|&at| now.saturating_sub(at) < CHAT_WINDOW_MICROS);
+        if self.sent.len() >= CHAT_BURST {
+            let oldest = self.sent[0];
+            return Err(ChatError::RateLimited {
+                retry_in_micros: (oldest + CHAT_WINDOW_MICROS).saturating_sub(now),
+            });
+        }
+        self.sent.push(now);
+        Ok(())
+    }
+}
+
+/// Chains the host muted, for the match they were muted in
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MuteList {
+    pub match_id: u64,
+    pub chains: Vec<ChainId>,
+}
+
+impl MuteList {
+    /// Whether `chain_id` is muted in match `match_id`
+    pub fn is_muted(&self, match_id: u64, chain_id: ChainId) -> bool {
+        self.match_id == match_id && self.chains.contains(&chain_id)
+    }
+
+    /// Chains muted in match `match_id`
+    pub fn muted_in(&self, match_id: u64) -> Vec<ChainId> {
+        match self.match_id == match_id {
+            true => self.chains.clone(),
+            false => Vec::new(),
+        }
+    }
+
+    /// Mute or unmute `chain_id` in match `match_id`, forgetting the mutes of an earlier match
+    pub fn set(&mut self, match_id: u64, chain_id: ChainId, muted: bool) {
+        if self.match_id != match_id {
+            self.match_id = match_id;
+            self.chains.clear();
+        }
+        self.chains.retain(|muted_chain| *muted_chain != chain_id);
+        if muted {
+            self.chains.push(chain_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::CryptoHash;
+
+    use super::*;
+
+    #[test]
+    fn text_is_cleaned_and_bursts_are_limited() {
+        assert_eq!(clean_text("  gg\n "), Ok("gg".to_string()));
+        assert_eq!(clean_text(" \t "), Err(ChatError::Empty));
+        assert_eq!(clean_text(&"a".repeat(MAX_CHAT_LENGTH + 1)), Err(ChatError::TooLong { max: MAX_CHAT_LENGTH }));
+        assert!(clean_text(&"é".repeat(MAX_CHAT_LENGTH)).is_ok());
+
+        let mut window = RateWindow::default();
+        for second in 0..CHAT_BURST as u64 {
+            window.try_send(second * 1_000_000).expect("within the burst");
+        }
+        assert_eq!(window.try_send(6_000_000), Err(ChatError::RateLimited { retry_in_micros: 4_000_000 }));
+        // The first message has left the window
+        assert_eq!(window.try_send(10_000_000), Ok(()));
+    }
+
+    #[test]
+    fn mutes_last_for_one_match() {
+        let chain = ChainId(CryptoHash::from([1; 32]));
+        let mut mutes = MuteList::default();
+        mutes.set(3, chain, true);
+        mutes.set(3, chain, true);
+        assert!(mutes.is_muted(3, chain));
+        assert_eq!(mutes.muted_in(3), vec![chain]);
+
+        // The next match on the same table starts with nobody muted
+        assert!(!mutes.is_muted(4, chain));
+        mutes.set(4, ChainId(CryptoHash::from([2; 32])), true);
+        assert!(!mutes.is_muted(4, chain));
+        assert!(mutes.muted_in(3).is_empty());
+
+        mutes.set(4, ChainId(CryptoHash::from([2; 32])), false);
+        assert!(mutes.chains.is_empty());
+    }
+
+    #[test]
+    fn reactions_cool_down_and_expire() {
+        assert_eq!(check_emote_cooldown(None, 0), Ok(()));
+        assert_eq!(check_emote_cooldown(Some(1_000_000), 2_000_000), Err(ChatError::EmoteCooldown { retry_in_micros: 2_000_000 }));
+        assert_eq!(check_emote_cooldown(Some(1_000_000), 4_000_000), Ok(()));
+
+        let reaction = |timestamp: u64| Reaction {
+            match_id: 0,
+            sender_chain: ChainId(CryptoHash::from([1; 32])),
+            nickname: "P1".to_string(),
+            seat: Some(0),
+            emote: Emote::NicePlay,
+            target_seat: None,
+            timestamp,
+        };
+        let mut recent = Vec::new();
+        for step in 0..12 {
+            keep_recent(&mut recent, reaction(step * 100_000));
+        }
+        assert_eq!(recent.len(), MAX_RECENT_REACTIONS);
+        assert_eq!(recent[0].timestamp, 200_000);
+        keep_recent(&mut recent, reaction(11_050_000));
+        assert_eq!(recent.iter().map(|kept| kept.timestamp).collect::<Vec<_>>(), vec![1_100_000, 11_050_000]);
+    }
+}
@@ -9,15 +9,17 @@ use linera_sdk::{
     views::{RootView, View},
     Contract, ContractRuntime,
 };
+use linera_sdk::abis::fungible::Account;
+use linot::escrow::EscrowError;
 use linot::{
-    Card, CardSuit, GameEvent, LinotAbi, LinotResponse, MatchData, MatchStatus,
-    Message, Operation, Player,
+    GameEvent, LinotAbi, LinotResponse, MatchData, MatchOptions, Message, Operation, Player,
 };
 
 /// The Linot contract
 pub struct LinotContract {
     state: LinotState,
     runtime: ContractRuntime<Self>,
+    as_of: Option<u64>,  // Time actions are applied at while settling overdue turns
 }
 
 linera_sdk::contract!(LinotContract);
@@ -36,7 +38,7 @@ impl Contract for LinotContract {
         let state = LinotState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load state");
-        LinotContract { state, runtime }
+        LinotContract { state, runtime, as_of: None }
     }
 
     async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
@@ -45,7 +47,10 @@ impl Contract for LinotContract {
     }
 
     async fn execute_operation(&mut self, operation: Operation) -> LinotResponse {
-        match operation {
+        // PLAY_CHAIN: any activity first catches up on expired turns
+        self.settle_overdue_turns().await;
+
+        let response = match operation {
             // USER_CHAIN operations
             Operation::Subscribe { play_chain_id } => {
                 self.handle_subscribe(play_chain_id).await;
@@ -57,13 +62,13 @@ impl Contract for LinotContract {
                 LinotResponse::Ok
             }
 
-            Operation::JoinMatch { play_chain_id, nickname } => {
-                self.handle_join_match(play_chain_id, nickname).await;
+            Operation::JoinMatch { play_chain_id, nickname, stake } => {
+                self.handle_join_match(play_chain_id, nickname, stake).await;
                 LinotResponse::Ok
             }
 
             // USER_CHAIN operation - Create match request
-            Operation::CreateMatch { max_players, nickname } => {
+            Operation::CreateMatch { max_players, nickname, options } => {
                 log::info!("CreateMatch operation on USER_CHAIN");
                 
                 // Verify we're subscribed to a PLAY_CHAIN
@@ -86,11 +91,21 @@ impl Contract for LinotContract {
                     }
                 };
                 
+                // Staked match: the host's fee goes into the PLAY_CHAIN escrow with the request
+                let options = options.unwrap_or_default();
+                if let Some(stake) = options.stake {
+                    if let Err(error) = stake.validate() {
+                        return LinotResponse::Error(error.to_string());
+                    }
+                    self.pay_entry_fee(creator_owner, play_chain_id, stake);
+                }
+                
                 // SEND MESSAGE TO PLAY_CHAIN 
                 self.runtime.prepare_message(Message::RequestCreateMatch {
                     creator_owner,
                     max_players,
                     nickname: nickname.clone(),
+                    options,
                 }).send_to(play_chain_id);
                 
                 // Update local USER_CHAIN state
@@ -121,12 +136,87 @@ impl Contract for LinotContract {
                     LinotResponse::Ok
                 } else {
                     // PLAY_CHAIN: Execute directly
-                    self.handle_start_match().await
+                    let player_owner = self.runtime.authenticated_signer();
+                    let response = self.handle_start_match(player_owner).await;
+                    self.run_bot_turns().await;
+                    response
+                }
+            }
+
+            Operation::CancelMatch => {
+                if let Some(play_chain_id) = self.state.subscribed_play_chain.get().as_ref() {
+                    // USER_CHAIN: Send message to PLAY_CHAIN
+                    let player_owner = match self.runtime.authenticated_signer() {
+                        Some(signer) => signer,
+                        None => {
+                            return LinotResponse::Error(
+                                "Signer required".to_string()
+                            );
+                        }
+                    };
+                    let message = Message::CancelMatchAction {
+                        player_owner,
+                    };
+                    self.runtime.prepare_message(message).send_to(*play_chain_id);
+                    LinotResponse::Ok
+                } else {
+                    // PLAY_CHAIN: Execute directly
+                    let player_owner = self.runtime.authenticated_signer();
+                    self.handle_cancel_match(player_owner).await
+                }
+            }
+
+            Operation::AddBot { seat, difficulty } => {
+                if let Some(play_chain_id) = self.state.subscribed_play_chain.get().as_ref() {
+                    // USER_CHAIN: Send message to PLAY_CHAIN
+                    let player_owner = match self.runtime.authenticated_signer() {
+                        Some(signer) => signer,
+                        None => {
+                            return LinotResponse::Error(
+                                "Signer required".to_string()
+                            );
+                        }
+                    };
+                    let message = Message::AddBotAction {
+                        player_owner,
+                        seat,
+                        difficulty,
+                    };
+                    self.runtime.prepare_message(message).send_to(*play_chain_id);
+                    LinotResponse::Ok
+                } else {
+                    // PLAY_CHAIN: Execute directly
+                    let player_owner = self.runtime.authenticated_signer();
+                    self.handle_add_bot(player_owner, seat, difficulty).await
+                }
+            }
+
+            Operation::SetTeams { teams } => {
+                if let Some(play_chain_id) = self.state.subscribed_play_chain.get().as_ref() {
+                    // USER_CHAIN: Send message to PLAY_CHAIN
+                    let player_owner = match self.runtime.authenticated_signer() {
+                        Some(signer) => signer,
+                        None => {
+                            return LinotResponse::Error(
+                                "Signer required".to_string()
+                            );
+                        }
+                    };
+                    let message = Message::SetTeamsAction {
+                        player_owner,
+                        teams,
+                    };
+                    self.runtime.prepare_message(message).send_to(*play_chain_id);
+                    LinotResponse::Ok
+                } else {
+                    // PLAY_CHAIN: Execute directly
+                    let player_owner = self.runtime.authenticated_signer();
+                    self.handle_set_teams(player_owner, teams).await
                 }
             }
 
             // Game actions (can be on either chain)
-            Operation::PlayCard { card_index, chosen_suit } => {
+            Operation::PlayCard { card_id, chosen_suit, expected_top_card_id, turn_number } => {
                 if let Some(play_chain_id) = self.state.subscribed_play_chain.get().as_ref() {
                     // USER_CHAIN: Send message to PLAY_CHAIN
                     let player_owner = match self.runtime.authenticated_signer() {
@@ -139,8 +229,10 @@ impl Contract for LinotContract {
                     };
                     let message = Message::PlayCardAction {
                         player_owner,
-                        card_index,
+                        card_id,
                         chosen_suit,
+                        expected_top_card_id,
+                        turn_number,
                     };
                     self.runtime.prepare_message(message).send_to(*play_chain_id);
                     LinotResponse::Ok
@@ -150,7 +242,32 @@ impl Contract for LinotContract {
                 }
             }
 
-            Operation::DrawCard => {
+            Operation::PlayCards { card_ids, chosen_suit, expected_top_card_id, turn_number } => {
+                if let Some(play_chain_id) = self.state.subscribed_play_chain.get().as_ref() {
+                    // USER_CHAIN: Send message to PLAY_CHAIN
+                    let player_owner = match self.runtime.authenticated_signer() {
+                        Some(signer) => signer,
+                        None => {
+                            return LinotResponse::Error(
+                                "Signer required".to_string()
+                            );
+                        }
+                    };
+                    let message = Message::PlayCardsAction {
+                        player_owner,
+                        card_ids,
+                        chosen_suit,
+                        expected_top_card_id,
+                        turn_number,
+                    };
+                    self.runtime.prepare_message(message).send_to(*play_chain_id);
+                    LinotResponse::Ok
+                } else {
+                    LinotResponse::Error("PlayCards should be sent via message".to_string())
+                }
+            }
+
+            Operation::DrawCard { turn_number } => {
                 if let Some(play_chain_id) = self.state.subscribed_play_chain.get().as_ref() {
                     // USER_CHAIN: Send message to PLAY_CHAIN
                     let player_owner = match self.runtime.authenticated_signer() {
@@ -163,6 +280,7 @@ impl Contract for LinotContract {
                     };
                     let message = Message::DrawCardAction {
                         player_owner,
+                        turn_number,
                     };
                     self.runtime.prepare_message(message).send_to(*play_chain_id);
                     LinotResponse::Ok
@@ -171,7 +289,7 @@ impl Contract for LinotContract {
                 }
             }
 
-            Operation::CallLastCard => {
+            Operation::CallLastCard { last_card_seq } => {
                 let player_owner = match self.runtime.authenticated_signer() {
                     Some(signer) => signer,
                     None => {
@@ -184,7 +302,7 @@ impl Contract for LinotContract {
                 // Determine if we're on PLAY_CHAIN or USER_CHAIN
                 if self.state.match_data.get().players.len() > 0 {
                     // On PLAY_CHAIN - handle directly
-                    self.handle_call_last_card_message(player_owner).await;
+                    self.handle_call_last_card_message(player_owner, last_card_seq).await;
                 } else {
                     // On USER_CHAIN - send message to PLAY_CHAIN
                     let play_chain_id = match self.state.subscribed_play_chain.get().as_ref() {
@@ -196,13 +314,13 @@ impl Contract for LinotContract {
                         }
                     };
                     
-                    let message = Message::CallLastCardAction { player_owner };
+                    let message = Message::CallLastCardAction { player_owner, last_card_seq };
                     self.runtime.prepare_message(message).send_to(play_chain_id);
                 }
                 LinotResponse::Ok
             }
             
-            Operation::ChallengeLastCard { player_index } => {
+            Operation::ChallengeLastCard { player_index, last_card_seq } => {
                 let challenger_owner = match self.runtime.authenticated_signer() {
                     Some(signer) => signer,
                     None => {
@@ -215,7 +333,7 @@ impl Contract for LinotContract {
                 // Determine chain type
                 if self.state.match_data.get().players.len() > 0 {
                     // On PLAY_CHAIN - handle directly
-                    self.handle_challenge_last_card_message(challenger_owner, player_index).await;
+                    self.handle_challenge_last_card_message(challenger_owner, player_index, last_card_seq).await;
                 } else {
                     // On USER_CHAIN - send message to PLAY_CHAIN
                     let play_chain_id = match self.state.subscribed_play_chain.get().as_ref() {
@@ -230,6 +348,7 @@ impl Contract for LinotContract {
                     let message = Message::ChallengeLastCardAction {
                         challenger_owner,
                         challenged_player_index: player_index,
+                        last_card_seq,
                     };
                     self.runtime.prepare_message(message).send_to(play_chain_id);
                 }
@@ -245,35 +364,165 @@ impl Contract for LinotContract {
             }
 
             Operation::LeaveMatch => {
+                // USER_CHAIN: Free the seat on the PLAY_CHAIN - only taken while the lobby is open
+                if let (Some(play_chain_id), Some(player_owner)) =
+                    (*self.state.subscribed_play_chain.get(), self.runtime.authenticated_signer())
+                {
+                    let message = Message::LeaveLobbyAction {
+                        player_owner,
+                    };
+                    self.runtime.prepare_message(message).send_to(play_chain_id);
+                }
                 self.state.user_status.set(UserStatus::Idle);
                 self.state.subscribed_play_chain.set(None);
                 self.state.local_match.set(None);
+                self.state.recent_reactions.set(Vec::new());
                 log::info!("Player left match");
                 LinotResponse::Ok
             }
-        }
+
+            // USER_CHAIN operations - watching without a seat
+            Operation::Spectate { play_chain_id } => {
+                if self.state.subscribed_play_chain.get().is_some() {
+                    return LinotResponse::Error(
+                        "Leave your current match before spectating".to_string()
+                    );
+                }
+                self.handle_spectate(play_chain_id).await;
+                LinotResponse::Ok
+            }
+
+            Operation::StopSpectating => {
+                self.handle_stop_spectating().await;
+                LinotResponse::Ok
+            }
+
+            Operation::SendChat { text } => {
+                let table = self.state.subscribed_play_chain.get()
+                    .or(*self.state.spectated_play_chain.get());
+                if let Some(play_chain_id) = table {
+                    // USER_CHAIN: Send message to PLAY_CHAIN
+                    let message = Message::ChatAction {
+                        nickname: self.state.player_nickname.get().clone(),
+                        text,
+                    };
+                    self.runtime.prepare_message(message).send_to(play_chain_id);
+                    LinotResponse::Ok
+                } else {
+                    // PLAY_CHAIN: Execute directly
+                    let sender_chain = self.runtime.chain_id();
+                    self.handle_chat_message(sender_chain, None, text).await
+                }
+            }
+
+            Operation::MuteChat { chain_id, muted } => {
+                if let Some(play_chain_id) = self.state.subscribed_play_chain.get().as_ref() {
+                    // USER_CHAIN: Send message to PLAY_CHAIN
+                    let player_owner = match self.runtime.authenticated_signer() {
+                        Some(signer) => signer,
+                        None => {
+                            return LinotResponse::Error(
+                                "Signer required".to_string()
+                            );
+                        }
+                    };
+                    let message = Message::MuteChatAction {
+                        player_owner,
+                        chain_id,
+                        muted,
+                    };
+                    self.runtime.prepare_message(message).send_to(*play_chain_id);
+                    LinotResponse::Ok
+                } else {
+                    // PLAY_CHAIN: Execute directly
+                    let player_owner = self.runtime.authenticated_signer();
+                    self.handle_mute_chat(player_owner, chain_id, muted).await
+                }
+            }
+
+            Operation::React { emote, target_seat } => {
+                let table = self.state.subscribed_play_chain.get()
+                    .or(*self.state.spectated_play_chain.get());
+                if let Some(play_chain_id) = table {
+                    // USER_CHAIN: Send message to PLAY_CHAIN
+                    let message = Message::ReactAction {
+                        nickname: self.state.player_nickname.get().clone(),
+                        emote,
+                        target_seat,
+                    };
+                    self.runtime.prepare_message(message).send_to(play_chain_id);
+                    LinotResponse::Ok
+                } else {
+                    // PLAY_CHAIN: Execute directly
+                    let sender_chain = self.runtime.chain_id();
+                    self.handle_react_message(sender_chain, None, emote, target_seat).await
+                }
+            }
+
+            // TOURNAMENT_CHAIN operations
+            Operation::CreateTournament { name, settings } => {
+                self.handle_create_tournament(name, settings).await
+            }
+
+            Operation::RegisterForTournament { tournament_chain_id, nickname } => {
+                self.handle_register_for_tournament(tournament_chain_id, nickname).await;
+                LinotResponse::Ok
+            }
+
+            Operation::StartTournament => {
+                let organizer = self.runtime.authenticated_signer();
+                self.handle_start_tournament(organizer).await
+            }
+        };
+
+        // PLAY_CHAIN: a finished staked match pays out right away
+        self.settle_escrow().await;
+        response
     }
 
     async fn execute_message(&mut self, message: Message) {
         let origin_chain_id = self.runtime.message_origin_chain_id()
             .expect("Chain ID missing from message");
         
+        // PLAY_CHAIN: any activity first catches up on expired turns
+        self.settle_overdue_turns().await;
+        
         match message {
             // PLAY_CHAIN: Handle create match request from USER_CHAIN
             Message::RequestCreateMatch {
                 creator_owner,
                 max_players,
                 nickname,
+                options,
             } => {
                 log::info!("PLAY_CHAIN received RequestCreateMatch from {:?}", origin_chain_id);
                 
-                // Execute match creation logic ON PLAY_CHAIN
-                let result = self.create_match_on_play_chain(
-                    creator_owner,
-                    origin_chain_id,  // creator's USER_CHAIN
-                   max_players,
-                    nickname,
-                ).await;
+                // Execute match creation logic ON PLAY_CHAIN - never over a pot still being held
+                let result = if self.escrow_outstanding() {
+                    Err(EscrowError::PotOutstanding.to_string())
+                } else if let Some(Err(error)) = options.stake.map(|stake| stake.validate()) {
+                    Err(error.to_string())
+                } else {
+                    self.create_match_on_play_chain(
+                        creator_owner,
+                        origin_chain_id,  // creator's USER_CHAIN
+                        max_players,
+                        nickname,
+                        options,
+                    ).await
+                };
+                
+                // The host paid with the request: hold the fee, or hand it back
+                if let Some(stake) = options.stake {
+                    let host_account = Account {
+                        chain_id: origin_chain_id,
+                        owner: creator_owner,
+                    };
+                    match &result {
+                        Ok(_) => self.open_escrow(stake, host_account),
+                        Err(_) => self.refund_payment(stake, host_account),
+                    }
+                }
                 
                 // Send result back to USER_CHAIN
                 let success = result.is_ok();
@@ -303,8 +552,9 @@ impl Contract for LinotContract {
                 player_owner,
                 player_chain,
                 nickname,
+                stake,
             } => {
-                self.handle_request_join_message(player_owner, player_chain, nickname).await;
+                self.handle_request_join_message(player_owner, player_chain, nickname, stake).await;
             }
             
             // USER_CHAIN: Confirmation of join from PLAY_CHAIN (triggers subscribe!)
@@ -315,32 +565,106 @@ impl Contract for LinotContract {
                 self.handle_join_confirmed(play_chain_id, success).await;
             }
             
-            Message::StartMatchAction { player_owner: _ } => {
+            Message::StartMatchAction { player_owner } => {
                 // Player requesting to start match on PLAY_CHAIN
                 log::info!("PLAY_CHAIN: Received StartMatchAction");
-                self.handle_start_match().await;
+                self.handle_start_match(Some(player_owner)).await;
+                self.run_bot_turns().await;
+            }
+
+            Message::CancelMatchAction { player_owner } => {
+                self.handle_cancel_match(Some(player_owner)).await;
+            }
+
+            Message::LeaveLobbyAction { player_owner } => {
+                self.handle_leave_lobby(player_owner).await;
+            }
+
+            Message::AddBotAction { player_owner, seat, difficulty } => {
+                self.handle_add_bot(Some(player_owner), seat, difficulty).await;
+            }
+
+            Message::SetTeamsAction { player_owner, teams } => {
+                self.handle_set_teams(Some(player_owner), teams).await;
             }
 
-            Message::PlayCardAction { player_owner, card_index, chosen_suit } => {
-                self.handle_play_card_message(player_owner, card_index, chosen_suit).await;
+            Message::PlayCardAction { player_owner, card_id, chosen_suit, expected_top_card_id, turn_number } => {
+                self.handle_play_card_message(player_owner, card_id, chosen_suit, expected_top_card_id, turn_number).await;
+                self.run_bot_turns().await;
             }
 
-            Message::DrawCardAction { player_owner } => {
-                self.handle_draw_card_message(player_owner).await;
+            Message::PlayCardsAction { player_owner, card_ids, chosen_suit, expected_top_card_id, turn_number } => {
+                self.handle_play_cards_message(player_owner, card_ids, chosen_suit, expected_top_card_id, turn_number).await;
+                self.run_bot_turns().await;
             }
 
-            Message::CallLastCardAction { player_owner } => {
-                self.handle_call_last_card_message(player_owner).await;
+            Message::DrawCardAction { player_owner, turn_number } => {
+                self.handle_draw_card_message(player_owner, turn_number).await;
+                self.run_bot_turns().await;
+            }
+
+            Message::CallLastCardAction { player_owner, last_card_seq } => {
+                self.handle_call_last_card_message(player_owner, last_card_seq).await;
             }
             
-            Message::ChallengeLastCardAction { challenger_owner, challenged_player_index } => {
-                self.handle_challenge_last_card_message(challenger_owner, challenged_player_index).await;
+            Message::ChallengeLastCardAction { challenger_owner, challenged_player_index, last_card_seq } => {
+                self.handle_challenge_last_card_message(challenger_owner, challenged_player_index, last_card_seq).await;
+            }
+
+            // PLAY_CHAIN: Spectator requesting to watch
+            Message::RequestSpectate { spectator_chain } => {
+                self.handle_request_spectate_message(spectator_chain).await;
+            }
+
+            // USER_CHAIN: Spectate confirmation from PLAY_CHAIN (triggers subscribe)
+            Message::SpectateConfirmed { play_chain_id, snapshot } => {
+                self.handle_spectate_confirmed(play_chain_id, snapshot).await;
+            }
+
+            Message::StopSpectatingAction { spectator_chain } => {
+                self.handle_stop_spectating_message(spectator_chain).await;
+            }
+
+            // PLAY_CHAIN: Table chat from a player or spectator
+            Message::ChatAction { nickname, text } => {
+                self.handle_chat_message(origin_chain_id, nickname, text).await;
+            }
+
+            Message::MuteChatAction { player_owner, chain_id, muted } => {
+                self.handle_mute_chat(Some(player_owner), chain_id, muted).await;
+            }
+
+            Message::ReactAction { nickname, emote, target_seat } => {
+                self.handle_react_message(origin_chain_id, nickname, emote, target_seat).await;
+            }
+
+            // TOURNAMENT_CHAIN: Player signing up
+            Message::RegisterEntrant { owner, player_chain, nickname } => {
+                self.handle_register_entrant_message(owner, player_chain, nickname).await;
+            }
+
+            // USER_CHAIN: Registration answer from the coordinator
+            Message::RegistrationResult { tournament_chain_id, success } => {
+                self.handle_registration_result(tournament_chain_id, success).await;
+            }
+
+            // PLAY_CHAIN: Table opened by a tournament coordinator
+            Message::OpenTable { tournament_chain_id, round, table, players, time_control, rules } => {
+                self.handle_open_table_message(tournament_chain_id, round, table, players, time_control, rules).await;
             }
         }
+
+        // PLAY_CHAIN: a finished staked match pays out right away
+        self.settle_escrow().await;
     }
 
     async fn process_streams(&mut self, updates: Vec<StreamUpdate>) {
-        self.handle_process_streams(updates).await;
+        // The coordinator only follows its tables; every other chain follows a match
+        if self.state.tournament.get().is_some() {
+            self.handle_tournament_streams(updates).await;
+        } else {
+            self.handle_process_streams(updates).await;
+        }
     }
 
     async fn store(mut self) {
@@ -354,62 +678,28 @@ impl Contract for LinotContract {
 
 impl LinotContract {
     /// Create initial match state
-    fn create_match(&mut self, max_players: u8, host_nickname: String) -> MatchData {
-        let mut players = vec![None; max_players as usize];
+    fn create_match(
+        &mut self,
+        max_players: u8,
+        host_nickname: String,
+        options: MatchOptions,
+    ) -> MatchData {
+        // Shuffle with system_time seed (kept for replays)
+        let seed = self.runtime.system_time().micros();
+        let time_control = options.time_control.unwrap_or_default();
+        let mut match_data = MatchData::new_lobby_with_time_control(max_players, seed, time_control);
+        match_data.clock = options.clock;
+        match_data.inactivity_policy = options.inactivity_policy.unwrap_or_default();
+        match_data.rules = options.rules.unwrap_or_default();
+        match_data.match_id = self.next_match_id();
 
         // Host is first player
-        players[0] = Some(Player::new(
+        match_data.players[0] = Some(Player::new(
             self.runtime.chain_id(),
             self.runtime.authenticated_signer().expect("Signer required"),
             host_nickname,
         ));
 
-        // Create and shuffle deck
-        let mut deck = Vec::with_capacity(61);
-        
-        // Regular suits (Circle, Triangle, Cross, Square) - values 1 to 14
-        for suit in [CardSuit::Circle, CardSuit::Triangle, CardSuit::Cross, CardSuit::Square] {
-            for value in 1..=14 {
-                deck.push(Card { suit, value });
-            }
-        }
-        
-        // Star suit - values 1 to 7
-        for value in 1..=7 {
-            deck.push(Card { suit: CardSuit::Star, value });
-        }
-        
-        // Whot cards (wild) - 5 total
-        for _ in 0..5 {
-            deck.push(Card { suit: CardSuit::Whot, value: 20 });
-        }
-        
-        // Fisher-Yates shuffle with system_time seed (proper randomness)
-        let seed = self.runtime.system_time().micros();
-        let mut state = seed;
-        let len = deck.len();
-        for i in (1..len).rev() {
-            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
-            let j = (state as usize) % (i + 1);
-            deck.swap(i, j);
-        }
-        
-        let deck_size = deck.len();
-
-        MatchData {
-            players,
-            deck,
-            discard_pile: Vec::new(),
-            current_player_index: 0,
-            status: MatchStatus::Waiting,
-            winner_index: None,
-            max_players,
-            deck_size,
-            pending_draw_stack: 0,
-            pending_draw_type: None,
-            active_demand_suit: None,
-            turn_start_time: None,
-            turn_duration: linot::TURN_TIMEOUT_MICROS,
-        }
+        match_data
     }
 }
@@ -0,0 +1,315 @@
+//! Entry fees for staked matches.
+//!
+//! Players pay the entry fee into an account owned by the Linot application on
+//! the PLAY_CHAIN when they create or join a match. Once the match is finished
+//! the pot goes to the winner - split across the winning team in team play, or
+//! between the players sharing the lead on a draw. A lobby that never starts
+//! hands every fee back. Tokens move through `TokenLedger`: the contract backs
+//! it with a fungible-token application, tests with `LocalLedger`.
+
+use std::collections::BTreeMap;
+
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::abis::fungible::Account;
+use linera_sdk::linera_base_types::{Amount, ApplicationId};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{MatchData, MatchStatus};
+
+/// Why a stake could not be paid, taken or handed out
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum EscrowError {
+    #[error("Entry fee must be more than zero")]
+    ZeroFee,
+    #[error("Payment does not match the match's entry fee")]
+    StakeMismatch,
+    #[error("Bots can't take a seat in a staked match")]
+    BotSeat,
+    #[error("A staked match is still open on this chain")]
+    PotOutstanding,
+    #[error("Insufficient balance")]
+    InsufficientBalance,
+    #[error("Token application rejected the transfer")]
+    TransferRejected,
+}
+
+/// Entry fee every seat pays into the pot
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SimpleObject, InputObject, PartialEq, Eq)]
+#[graphql(input_name = "StakeInput")]
+pub struct Stake {
+    pub token: ApplicationId,  // Fungible-token application the fee is paid in
+    pub entry_fee: Amount,
+}
+
+impl Stake {
+    pub fn validate(&self) -> Result<(), EscrowError> {
+        if self.entry_fee == Amount::ZERO {
+            return Err(EscrowError::ZeroFee);
+        }
+        Ok(())
+    }
+}
+
+/// Fee paid for one seat
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Deposit {
+    pub seat: usize,
+    pub account: Account,  // Where the fee came from - payouts and refunds go back here
+    pub amount: Amount,
+}
+
+/// Tokens sent out of the pot
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Payout {
+    pub seat: usize,
+    pub account: Account,
+    pub amount: Amount,
+}
+
+/// Pot of a staked match, held by the PLAY_CHAIN application
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Escrow {
+    pub stake: Stake,
+    pub deposits: Vec<Deposit>,
+    pub settled: bool,  // Paid out or refunded - nothing left in the pot
+}
+
+impl Escrow {
+    pub fn new(stake: Stake) -> Self {
+        Self {
+            stake,
+            deposits: Vec::new(),
+            settled: false,
+        }
+    }
+
+    /// Tokens held for this match
+    pub fn pot(&self) -> Amount {
+        self.deposits.iter()
+            .fold(Amount::ZERO, |pot, deposit| pot.saturating_add(deposit.amount))
+    }
+
+    /// Reject a payment made for a different fee or token
+    pub fn check_payment(&self, paid: Option<Stake>) -> Result<(), EscrowError> {
+        if paid != Some(self.stake) {
+            return Err(EscrowError::StakeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Record the fee paid for `seat` from `account`
+    pub fn deposit(&mut self, seat: usize, account: Account) {
+        self.deposits.push(Deposit {
+            seat,
+            account,
+            amount: self.stake.entry_fee,
+        });
+    }
+
+    /// Take the fee paid for `seat` out of the pot, to send back to whoever paid it
+    pub fn withdraw(&mut self, seat: usize) -> Option<Payout> {
+        let index = self.deposits.iter().position(|deposit| deposit.seat == seat)?;
+        let deposit = self.deposits.remove(index);
+        Some(Payout {
+            seat: deposit.seat,
+            account: deposit.account,
+            amount: deposit.amount,
+        })
+    }
+
+    /// Every fee back to whoever paid it
+    pub fn refunds(&self) -> Vec<Payout> {
+        self.deposits.iter()
+            .map(|deposit| Payout {
+                seat: deposit.seat,
+                account: deposit.account,
+                amount: deposit.amount,
+            })
+            .collect()
+    }
+
+    /// How the pot is shared out at the end of `match_data`: the winner takes
+    /// it all, a winning team or a draw splits it evenly. Seats nobody paid for
+    /// can't collect, and when no recipient paid every fee is refunded.
+    /// Empty until the match is finished.
+    pub fn payouts(&self, match_data: &MatchData) -> Vec<Payout> {
+        if match_data.status != MatchStatus::Finished {
+            return Vec::new();
+        }
+        let recipients: Vec<usize> = match (match_data.winning_team, match_data.winner_index) {
+            (Some(team), _) => (0..match_data.players.len())
+                .filter(|&seat| match_data.team_of(seat) == Some(team))
+                .collect(),
+            (None, Some(winner)) => vec![winner],
+            (None, None) => match_data.card_count_leaders(),
+        };
+        let shares: Vec<&Deposit> = self.deposits.iter()
+            .filter(|deposit| recipients.contains(&deposit.seat))
+            .collect();
+        if shares.is_empty() {
+            return self.refunds();
+        }
+
+        // Whatever doesn't divide evenly goes to the first share
+        let pot = u128::from(self.pot());
+        let count = shares.len() as u128;
+        let remainder = Amount::from_attos(pot % count);
+        shares.iter()
+            .enumerate()
+            .map(|(index, deposit)| Payout {
+                seat: deposit.seat,
+                account: deposit.account,
+                amount: match index {
+                    0 => Amount::from_attos(pot / count).saturating_add(remainder),
+                    _ => Amount::from_attos(pot / count),
+                },
+            })
+            .collect()
+    }
+}
+
+/// Token movements the escrow needs
+pub trait TokenLedger {
+    /// Move `amount` from `source` to `target`
+    fn transfer(&mut self, source: Account, amount: Amount, target: Account) -> Result<(), EscrowError>;
+}
+
+/// Send each payout from the escrow account `source`
+pub fn pay_out(ledger: &mut impl TokenLedger, source: Account, payouts: &[Payout]) -> Result<(), EscrowError> {
+    for payout in payouts {
+        ledger.transfer(source, payout.amount, payout.account)?;
+    }
+    Ok(())
+}
+
+/// In-memory stand-in for a fungible-token application
+#[derive(Clone, Debug, Default)]
+pub struct LocalLedger {
+    pub balances: BTreeMap<Account, Amount>,
+}
+
+impl LocalLedger {
+    pub fn balance(&self, account: &Account) -> Amount {
+        self.balances.get(account).copied().unwrap_or(Amount::ZERO)
+    }
+}
+
+impl TokenLedger for LocalLedger {
+    fn transfer(&mut self, source: Account, amount: Amount, target: Account) -> Result<(), EscrowError> {
+        let remaining = self.balance(&source)
+            .try_sub(amount)
+            .map_err(|_| EscrowError::InsufficientBalance)?;
+        self.balances.insert(source, remaining);
+        let credited = self.balance(&target).saturating_add(amount);
+        self.balances.insert(target, credited);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::{AccountOwner, ChainId, CryptoHash};
+
+    use super::*;
+    use crate::game::seated_match;
+    use crate::{Card, CardSuit};
+
+    fn account(index: u8) -> Account {
+        Account {
+            chain_id: ChainId(CryptoHash::from([index; 32])),
+            owner: AccountOwner::Reserved(index + 1),
+        }
+    }
+
+    fn stake() -> Stake {
+        Stake {
+            token: ApplicationId::new(CryptoHash::from([7; 32])),
+            entry_fee: Amount::from_attos(10),
+        }
+    }
+
+    /// Lobby where every seat has paid the fee into `escrow_account` - seat `i` pays from `account(i)`
+    fn staked_lobby(seats: usize, ledger: &mut LocalLedger, escrow_account: Account) -> MatchData {
+        let mut match_data = seated_match(&vec![true; seats], 3);
+        let mut escrow = Escrow::new(stake());
+        for seat in 0..seats {
+            let payer = account(seat as u8);
+            ledger.balances.insert(payer, Amount::from_attos(10));
+            escrow.check_payment(Some(stake())).expect("fee matches");
+            ledger.transfer(payer, escrow.stake.entry_fee, escrow_account).expect("fee is paid");
+            escrow.deposit(seat, payer);
+        }
+        match_data.escrow = Some(escrow);
+        match_data
+    }
+
+    #[test]
+    fn winner_takes_the_pot_and_a_cancelled_lobby_refunds() {
+        let escrow_account = account(9);
+        let mut ledger = LocalLedger::default();
+        let mut match_data = staked_lobby(3, &mut ledger, escrow_account);
+        let escrow = match_data.escrow.clone().expect("staked");
+        assert_eq!(escrow.pot(), Amount::from_attos(30));
+        assert_eq!(escrow.check_payment(None), Err(EscrowError::StakeMismatch));
+
+        // Nothing is paid out while the lobby is open
+        assert!(escrow.payouts(&match_data).is_empty());
+        pay_out(&mut ledger, escrow_account, &escrow.refunds()).expect("pot covers the refunds");
+        assert_eq!(ledger.balance(&escrow_account), Amount::ZERO);
+        assert_eq!(ledger.balance(&account(2)), Amount::from_attos(10));
+
+        let mut ledger = LocalLedger::default();
+        match_data = staked_lobby(3, &mut ledger, escrow_account);
+        match_data.status = MatchStatus::Finished;
+        match_data.winner_index = Some(1);
+        let payouts = escrow.payouts(&match_data);
+        pay_out(&mut ledger, escrow_account, &payouts).expect("pot covers the payout");
+        assert_eq!(ledger.balance(&account(1)), Amount::from_attos(30));
+        assert_eq!(ledger.balance(&account(0)), Amount::ZERO);
+        assert_eq!(pay_out(&mut ledger, escrow_account, &payouts), Err(EscrowError::InsufficientBalance));
+    }
+
+    #[test]
+    fn a_joiner_withdraws_their_own_fee() {
+        let escrow_account = account(9);
+        let mut ledger = LocalLedger::default();
+        let match_data = staked_lobby(3, &mut ledger, escrow_account);
+        let mut escrow = match_data.escrow.expect("staked");
+
+        let refund = escrow.withdraw(2).expect("seat 2 paid");
+        assert_eq!((refund.account, refund.amount), (account(2), Amount::from_attos(10)));
+        assert_eq!(escrow.withdraw(2), None);
+        pay_out(&mut ledger, escrow_account, &[refund]).expect("pot covers the refund");
+        assert_eq!(ledger.balance(&account(2)), Amount::from_attos(10));
+
+        // The rest of the pot still goes back to the seats that stayed
+        assert_eq!(escrow.pot(), Amount::from_attos(20));
+        assert_eq!(escrow.refunds().iter().map(|payout| payout.seat).collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn teams_and_draws_split_the_pot() {
+        let escrow_account = account(9);
+        let mut ledger = LocalLedger::default();
+        let mut match_data = staked_lobby(4, &mut ledger, escrow_account);
+        let escrow = match_data.escrow.clone().expect("staked");
+        match_data.teams = vec![0, 1, 0, 1];
+        match_data.status = MatchStatus::Finished;
+        match_data.winner_index = Some(3);
+        match_data.winning_team = Some(1);
+        let seats: Vec<(usize, Amount)> = escrow.payouts(&match_data).iter().map(|payout| (payout.seat, payout.amount)).collect();
+        assert_eq!(seats, vec![(1, Amount::from_attos(20)), (3, Amount::from_attos(20))]);
+
+        // Three-way tie on cards held: 40 doesn't divide by three
+        let mut match_data = staked_lobby(4, &mut ledger, escrow_account);
+        match_data.status = MatchStatus::Finished;
+        for (seat, size) in [2, 2, 5, 2].into_iter().enumerate() {
+            match_data.players[seat].as_mut().expect("seated").hand = vec![Card::new(CardSuit::Star, 1); size];
+        }
+        let payouts = escrow.payouts(&match_data);
+        let amounts: Vec<u128> = payouts.iter().map(|payout| u128::from(payout.amount)).collect();
+        assert_eq!(amounts, vec![14, 13, 13]);
+        assert_eq!(payouts.iter().map(|payout| payout.seat).collect::<Vec<_>>(), vec![0, 1, 3]);
+    }
+}
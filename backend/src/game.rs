@@ -0,0 +1,1534 @@
+//! Whot rules shared by the PLAY_CHAIN contract, the service and the replayer.
+//!
+//! Every transition takes the current time (micros) as an argument instead of
+//! reading the clock, so replaying a move log rebuilds the exact same `MatchData`.
+
+use std::ops::RangeInclusive;
+
+use async_graphql::SimpleObject;
+use linera_sdk::linera_base_types::AccountOwner;
+use thiserror::Error;
+
+use crate::bot::BotDifficulty;
+use crate::{
+    Card, CardSuit, HandAnnouncement, HoldOnRule, InactivityPolicy, MarketDraw, MatchData,
+    MatchStatus, PendingPenalty, PlayDirection, Player, RuleSet, SpecialEffect, TeamScore,
+    TimeControl, WhotDemand, INITIAL_HAND_SIZE, MIN_PLAYERS,
+};
+
+/// Value carried by Whot (wild) cards
+pub const WHOT_VALUE: u8 = 20;
+
+/// Card value that suspends the next player when `RuleSet::suspension` is on
+pub const SUSPENSION_VALUE: u8 = 8;
+
+/// Card value that reverses the play direction when `RuleSet::reverse` is on
+pub const REVERSE_VALUE: u8 = 10;
+
+/// Card value of Hold On
+pub const HOLD_ON_VALUE: u8 = 1;
+
+/// Card value of General Market
+pub const MARKET_VALUE: u8 = 14;
+
+/// Upper bound on `RuleSet::market_draw`
+pub const MAX_MARKET_DRAW: u8 = 4;
+
+/// Cards drawn by a player caught without calling last card
+pub const CHALLENGE_PENALTY_CARDS: u8 = 2;
+
+/// Consecutive timeouts after which a seat is removed (earlier ones draw 1, then 2, ...)
+pub const MAX_CONSECUTIVE_TIMEOUTS: u8 = 3;
+
+/// Bot that takes over a seat removed for inactivity
+pub const INACTIVITY_BOT: BotDifficulty = BotDifficulty::Greedy;
+
+/// Why an action was rejected by the rules
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum GameError {
+    #[error("Match is not in progress")]
+    NotInProgress,
+    #[error("Match has already started")]
+    AlreadyStarted,
+    #[error("Not enough players. Need at least {needed}. Current: {current}")]
+    NotEnoughPlayers { needed: u8, current: usize },
+    #[error("No empty slots")]
+    MatchFull,
+    #[error("Invalid player index")]
+    InvalidSeat,
+    #[error("Seat is already taken")]
+    SeatTaken,
+    #[error("Only the host can do that")]
+    NotHost,
+    #[error("Not the current player's turn")]
+    NotYourTurn,
+    #[error("Invalid card index")]
+    InvalidCardIndex,
+    #[error("Invalid card play")]
+    InvalidPlay,
+    #[error("No cards to play")]
+    NoCards,
+    #[error("The same card was listed twice")]
+    DuplicateCard,
+    #[error("Cards played together must share a value")]
+    MixedValues,
+    #[error("A Whot card must name Circle, Triangle, Cross, Square or Star")]
+    SuitRequired,
+    #[error("Deck empty but player has valid cards to play - cannot draw")]
+    MustPlay,
+    #[error("Player has run out of time")]
+    OutOfTime,
+    #[error("Card is not in the player's hand")]
+    CardNotInHand,
+    #[error("Top card changed since the action was issued")]
+    StaleTopCard,
+    #[error("Players can't challenge themselves")]
+    SelfChallenge,
+    #[error("Challenge window closed - the challenged player's turn has begun")]
+    ChallengeWindowClosed,
+    #[error("Invalid rules: {0}")]
+    InvalidRules(String),
+    #[error("Invalid teams: {0}")]
+    InvalidTeams(String),
+    #[error("Action was issued for turn {issued} but the match is at turn {current}")]
+    StaleTurn { issued: u64, current: u64 },
+    #[error("Last-card action was issued at call {issued} but the match is at call {current}")]
+    StaleLastCard { issued: u64, current: u64 },
+}
+
+/// Result of a successful card play
+#[derive(Clone, Debug)]
+pub struct PlayOutcome {
+    pub card: Card,
+    /// Same-value cards played together with `card`, in order
+    pub more_cards: Vec<Card>,
+    /// Cards every other player got from General Market (empty for other cards)
+    pub market_draws: Vec<MarketDraw>,
+    /// Check-up on a win, semi-last card when the rules ask for it
+    pub announcement: Option<HandAnnouncement>,
+    /// First effect among the cards played - each card applies its own (none apply when the play wins the match)
+    pub special_effect: Option<SpecialEffect>,
+    /// Pending penalty cards drawn before the card could be played
+    pub penalty_drawn: u8,
+    /// Seat whose turn it is now (after special effects)
+    pub next_seat: usize,
+    /// Set when the card emptied the player's hand
+    pub winner: Option<usize>,
+}
+
+/// Result of a successful draw
+#[derive(Clone, Debug)]
+pub enum DrawOutcome {
+    /// Cards were drawn and the turn passed on
+    Drawn { count: u8, penalty: u8, next_seat: usize },
+    /// Deck empty and no playable card - match ended on card count (`None` is a draw)
+    DeckExhausted { winner: Option<usize> },
+}
+
+/// Result of a last-card call
+#[derive(Clone, Debug)]
+pub struct LastCardOutcome {
+    /// False when the hand was too big - the caller drew `penalty_cards` instead
+    pub accepted: bool,
+    pub hand_size: usize,
+    pub penalty_cards: u8,
+}
+
+/// Result of a challenge against a player holding one card
+#[derive(Clone, Debug)]
+pub struct ChallengeOutcome {
+    pub success: bool,
+    /// Drawn by the challenged player on success, by the challenger otherwise
+    pub penalty_cards: u8,
+}
+
+/// Result of an expired turn
+#[derive(Clone, Debug)]
+pub struct TimeoutOutcome {
+    pub seat: usize,
+    /// Cards dealt for this timeout, pending penalty included
+    pub cards_drawn: u8,
+    /// Pending Pick Two / Pick Three penalty served by the timed-out player
+    pub penalty_served: u8,
+    /// Timeouts in a row for this seat, including this one
+    pub consecutive_timeouts: u8,
+    /// Set when the seat was removed for inactivity
+    pub removed: Option<InactivityPolicy>,
+    pub next_seat: usize,
+    /// Set when removing the seat left a single player
+    pub winner: Option<usize>,
+}
+
+/// Result of a player leaving the match mid-game
+#[derive(Clone, Debug)]
+pub struct ForfeitOutcome {
+    pub seat: usize,
+    pub next_seat: usize,
+    /// Set when a single player is left in the match
+    pub winner: Option<usize>,
+}
+
+/// A card the current player may play right now
+#[derive(Clone, Debug, SimpleObject)]
+pub struct PlayableCard {
+    pub index: usize,                   // Position in the hand
+    pub card: Card,
+    pub blocks_penalty: bool,           // Passes the pending penalty on instead of drawing it
+    pub requires_suit: bool,            // Whot card - a chosen_suit must be sent
+}
+
+/// What a seat is allowed to do, computed with the same rules the contract enforces
+#[derive(Clone, Debug, Default, SimpleObject)]
+pub struct LegalMoves {
+    pub is_your_turn: bool,
+    pub playable: Vec<PlayableCard>,    // Empty when it's not this seat's turn
+    pub can_draw: bool,
+    pub must_draw: bool,                // No playable card - drawing is the only move
+    pub pending_penalty: u8,            // Cards drawn first unless a blocking card is played
+    pub active_demand_suit: Option<CardSuit>,
+}
+
+/// The 68-card Whot deck in a fixed order; a card's id is its position here
+pub fn standard_deck() -> Vec<Card> {
+    let mut deck = Vec::with_capacity(68);
+
+    // Regular suits (Circle, Triangle, Cross, Square) - values 1 to 14, then Star - values 1 to 7
+    for suit in [CardSuit::Circle, CardSuit::Triangle, CardSuit::Cross, CardSuit::Square, CardSuit::Star] {
+        for value in suit_values(suit) {
+            deck.push(Card::new(suit, value));
+        }
+    }
+
+    // Whot cards (wild) - 5 total
+    for _ in 0..5 {
+        deck.push(Card::new(CardSuit::Whot, WHOT_VALUE));
+    }
+
+    for (id, card) in deck.iter_mut().enumerate() {
+        card.id = id as u8;
+    }
+    deck
+}
+
+/// Values the standard deck holds for `suit`
+pub fn suit_values(suit: CardSuit) -> RangeInclusive<u8> {
+    match suit {
+        CardSuit::Star => 1..=7,
+        CardSuit::Whot => WHOT_VALUE..=WHOT_VALUE,
+        _ => 1..=14,
+    }
+}
+
+/// Fisher-Yates shuffle driven by an LCG, so the same seed always gives the same deck
+pub fn shuffle(deck: &mut [Card], seed: u64) {
+    let mut state = seed;
+    for i in (1..deck.len()).rev() {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let j = (state as usize) % (i + 1);
+        deck.swap(i, j);
+    }
+}
+
+impl RuleSet {
+    /// Reject rule combinations the engine can't play
+    pub fn validate(&self) -> Result<(), GameError> {
+        if self.market_draw == 0 || self.market_draw > MAX_MARKET_DRAW {
+            return Err(GameError::InvalidRules(format!("market draw must be between 1 and {}", MAX_MARKET_DRAW)));
+        }
+        if self.last_card_window == 0 || self.last_card_window > INITIAL_HAND_SIZE {
+            return Err(GameError::InvalidRules(format!("last card window must be between 1 and {}", INITIAL_HAND_SIZE)));
+        }
+        Ok(())
+    }
+}
+
+impl Card {
+    /// Card with the id it has in the standard deck; Whot cards are
+    /// interchangeable, so they all get the id of the first one
+    pub fn new(suit: CardSuit, value: u8) -> Card {
+        let id = match suit {
+            CardSuit::Circle => value.saturating_sub(1),
+            CardSuit::Triangle => 14 + value.saturating_sub(1),
+            CardSuit::Cross => 28 + value.saturating_sub(1),
+            CardSuit::Square => 42 + value.saturating_sub(1),
+            CardSuit::Star => 56 + value.saturating_sub(1),
+            CardSuit::Whot => 63,
+        };
+        Card { id, suit, value }
+    }
+
+    /// Special effect triggered by playing this card
+    pub fn special_effect(&self, chosen_suit: Option<CardSuit>) -> Option<SpecialEffect> {
+        match self.value {
+            2 => Some(SpecialEffect::PickTwo),
+            5 if self.suit != CardSuit::Star => Some(SpecialEffect::PickThree),
+            HOLD_ON_VALUE => Some(SpecialEffect::HoldOn),
+            MARKET_VALUE => Some(SpecialEffect::GeneralMarket),
+            WHOT_VALUE => chosen_suit.map(|suit| SpecialEffect::WhotPlayed { chosen_suit: suit }),
+            _ => None,
+        }
+    }
+}
+
+impl MatchData {
+    /// Fresh lobby with a deck shuffled from `seed`
+    pub fn new_lobby(max_players: u8, seed: u64) -> MatchData {
+        Self::new_lobby_with_time_control(max_players, seed, TimeControl::default())
+    }
+
+    /// Fresh lobby using the turn timer of `time_control`
+    pub fn new_lobby_with_time_control(max_players: u8, seed: u64, time_control: TimeControl) -> MatchData {
+        let mut deck = standard_deck();
+        shuffle(&mut deck, seed);
+
+        MatchData {
+            players: vec![None; max_players as usize],
+            deck_size: deck.len(),
+            deck,
+            max_players,
+            seed,
+            turn_duration: time_control.turn_duration_micros(),
+            time_control,
+            ..MatchData::default()
+        }
+    }
+
+    /// Seat of the player owned by `owner`
+    pub fn seat_of(&self, owner: &AccountOwner) -> Option<usize> {
+        self.players.iter()
+            .position(|p| p.as_ref().is_some_and(|player| player.owner == *owner))
+    }
+
+    /// Number of seated players
+    pub fn player_count(&self) -> usize {
+        self.players.iter().filter(|p| p.is_some()).count()
+    }
+
+    /// Whether `seat` is taken by a player still in the match
+    pub fn is_active(&self, seat: usize) -> bool {
+        self.players.get(seat)
+            .and_then(|p| p.as_ref())
+            .is_some_and(|player| !player.forfeited)
+    }
+
+    /// Next active seat after `from` in the current play direction
+    pub fn next_seat(&self, from: usize) -> usize {
+        let len = self.players.len();
+        let step = |seat: usize| match self.direction {
+            PlayDirection::Clockwise => (seat + 1) % len,
+            PlayDirection::Anticlockwise => (seat + len - 1) % len,
+        };
+        let mut next = step(from);
+        for _ in 0..len {
+            if self.is_active(next) {
+                break;
+            }
+            next = step(next);
+        }
+        next
+    }
+
+    /// Effect of playing `card` under this match's rules
+    pub fn effect_of(&self, card: &Card, chosen_suit: Option<CardSuit>) -> Option<SpecialEffect> {
+        match card.value {
+            SUSPENSION_VALUE if self.rules.suspension => Some(SpecialEffect::Suspension),
+            REVERSE_VALUE if self.rules.reverse => Some(SpecialEffect::Reverse),
+            _ => card.special_effect(chosen_suit),
+        }
+    }
+
+    /// Whether `card` can be played on the current top card / demand suit
+    pub fn is_playable(&self, card: &Card) -> bool {
+        if self.blocks_penalty(card) {
+            // An answer to the pending penalty goes on regardless of suit
+            return true;
+        }
+        if card.value == WHOT_VALUE {
+            // WHOT goes on anything, except an active demand when the rules forbid Whot on Whot
+            return self.whot_demand.is_none() || self.rules.whot_on_whot;
+        }
+        if let Some(demand) = &self.whot_demand {
+            // WHOT was played - match against chosen suit
+            card.suit == demand.suit
+        } else if let Some(top) = self.discard_pile.last() {
+            // Normal validation against top card
+            card.suit == top.suit || card.value == top.value
+        } else {
+            // First card - always valid
+            true
+        }
+    }
+
+    /// Whether `card` answers the pending penalty instead of drawing it
+    pub fn blocks_penalty(&self, card: &Card) -> bool {
+        let Some(penalty) = &self.pending_penalty else {
+            return false;
+        };
+        match card.special_effect(None) {
+            // Same pick card stacks; the other one only with cross-stacking
+            Some(SpecialEffect::PickTwo) => penalty.source.value == 2 || self.rules.cross_stacking,
+            Some(SpecialEffect::PickThree) => penalty.source.value == 5 || self.rules.cross_stacking,
+            Some(SpecialEffect::HoldOn) => self.rules.hold_on_reflects,
+            _ => card.value == WHOT_VALUE && self.rules.whot_defence,
+        }
+    }
+
+    /// Cards the current player has to draw unless they answer the penalty
+    pub fn pending_total(&self) -> u8 {
+        self.pending_penalty.as_ref().map_or(0, |penalty| penalty.total)
+    }
+
+    /// Legal moves for `seat` in the current position
+    pub fn legal_moves(&self, seat: usize) -> LegalMoves {
+        let mut moves = LegalMoves {
+            pending_penalty: self.pending_total(),
+            active_demand_suit: self.whot_demand.as_ref().map(|demand| demand.suit),
+            ..LegalMoves::default()
+        };
+        if self.check_turn(seat).is_err() {
+            return moves;
+        }
+        let Some(player) = self.players.get(seat).and_then(|p| p.as_ref()) else {
+            return moves;
+        };
+
+        moves.is_your_turn = true;
+        moves.playable = player.hand.iter()
+            .enumerate()
+            .filter(|(_, card)| self.is_playable(card))
+            .map(|(index, card)| PlayableCard {
+                index,
+                card: card.clone(),
+                blocks_penalty: self.blocks_penalty(card),
+                requires_suit: card.value == WHOT_VALUE,
+            })
+            .collect();
+        moves.can_draw = self.draw_allowed(!moves.playable.is_empty());
+        moves.must_draw = moves.playable.is_empty();
+        moves
+    }
+
+    /// Whether the player to move may draw - shared by `draw_card` and `legal_moves`.
+    /// With an empty deck a player holding a valid card must play it.
+    fn draw_allowed(&self, has_playable: bool) -> bool {
+        !self.deck.is_empty() || !has_playable
+    }
+
+    /// Whether seats are split into teams
+    pub fn has_teams(&self) -> bool {
+        !self.teams.is_empty()
+    }
+
+    /// Team of `seat` in team play
+    pub fn team_of(&self, seat: usize) -> Option<u8> {
+        self.teams.get(seat).copied()
+    }
+
+    /// Assign every seat a team while the lobby is open; an empty list turns team play off.
+    /// Teams are numbered from 0, need at least two members and must all be the same size.
+    pub fn set_teams(&mut self, teams: Vec<u8>) -> Result<(), GameError> {
+        if self.status != MatchStatus::Waiting {
+            return Err(GameError::AlreadyStarted);
+        }
+        if !teams.is_empty() {
+            if teams.len() != self.players.len() {
+                return Err(GameError::InvalidTeams(format!("expected a team for each of the {} seats", self.players.len())));
+            }
+            let team_count = teams.iter().max().map_or(0, |&max| max as usize + 1);
+            let mut sizes = vec![0usize; team_count];
+            for &team in &teams {
+                sizes[team as usize] += 1;
+            }
+            if team_count < 2 || sizes.iter().any(|&size| size < 2 || size != sizes[0]) {
+                return Err(GameError::InvalidTeams(
+                    "teams must be numbered from 0 and have the same size of at least two".to_string(),
+                ));
+            }
+        }
+        self.teams = teams;
+        Ok(())
+    }
+
+    /// Cards held by each team, members who left the match not counted
+    pub fn scores_by_team(&self) -> Vec<TeamScore> {
+        let mut scores: Vec<TeamScore> = Vec::new();
+        for (seat, &team) in self.teams.iter().enumerate() {
+            let index = match scores.iter().position(|score| score.team == team) {
+                Some(index) => index,
+                None => {
+                    scores.push(TeamScore { team, seats: Vec::new(), cards_held: 0 });
+                    scores.len() - 1
+                }
+            };
+            scores[index].seats.push(seat);
+            if let Some(player) = self.players.get(seat).and_then(|p| p.as_ref()).filter(|player| !player.forfeited) {
+                scores[index].cards_held += player.hand.len();
+            }
+        }
+        scores.sort_by_key(|score| score.team);
+        scores
+    }
+
+    /// Seats sharing the lead on cards held: the players holding the fewest, or in
+    /// team play the members still in the match of the teams holding the fewest in total
+    pub fn card_count_leaders(&self) -> Vec<usize> {
+        let hand_size = |seat: usize| self.players[seat].as_ref().map_or(0, |player| player.hand.len());
+        let active: Vec<usize> = (0..self.players.len())
+            .filter(|&seat| self.is_active(seat))
+            .collect();
+
+        if self.has_teams() {
+            // A team whose members all left holds nothing but can't lead
+            let scores: Vec<TeamScore> = self.scores_by_team()
+                .into_iter()
+                .filter(|score| score.seats.iter().any(|seat| active.contains(seat)))
+                .collect();
+            let min_held = scores.iter().map(|score| score.cards_held).min().unwrap_or(0);
+            return active.into_iter()
+                .filter(|seat| scores.iter().any(|score| score.cards_held == min_held && score.seats.contains(seat)))
+                .collect();
+        }
+
+        let min_count = active.iter().map(|&seat| hand_size(seat)).min().unwrap_or(0);
+        active.into_iter()
+            .filter(|&seat| hand_size(seat) == min_count)
+            .collect()
+    }
+
+    /// Seat a player in the first empty slot
+    pub fn seat_player(&mut self, player: Player) -> Result<usize, GameError> {
+        let index = self.players.iter()
+            .position(|p| p.is_none())
+            .ok_or(GameError::MatchFull)?;
+        self.players[index] = Some(player);
+        Ok(index)
+    }
+
+    /// Seat a player in a specific empty slot while the lobby is open
+    pub fn seat_player_at(&mut self, seat: usize, player: Player) -> Result<(), GameError> {
+        if self.status != MatchStatus::Waiting {
+            return Err(GameError::AlreadyStarted);
+        }
+        match self.players.get(seat) {
+            None => Err(GameError::InvalidSeat),
+            Some(Some(_)) => Err(GameError::SeatTaken),
+            Some(None) => {
+                self.players[seat] = Some(player);
+                Ok(())
+            }
+        }
+    }
+
+    /// Free a seat while the lobby is open, handing back who sat there
+    pub fn vacate_seat(&mut self, seat: usize) -> Result<Player, GameError> {
+        if self.status != MatchStatus::Waiting {
+            return Err(GameError::AlreadyStarted);
+        }
+        self.players.get_mut(seat)
+            .and_then(Option::take)
+            .ok_or(GameError::InvalidSeat)
+    }
+
+    /// Whether `owner` holds the host seat (seat 0)
+    pub fn is_host(&self, owner: &AccountOwner) -> bool {
+        self.seat_of(owner) == Some(0)
+    }
+
+    /// Deal hands, turn up the first card and hand the turn to seat 0
+    pub fn start(&mut self, now: u64) -> Result<(), GameError> {
+        if self.status != MatchStatus::Waiting {
+            return Err(GameError::AlreadyStarted);
+        }
+        let current = self.player_count();
+        if current < MIN_PLAYERS as usize {
+            return Err(GameError::NotEnoughPlayers { needed: MIN_PLAYERS, current });
+        }
+        if self.has_teams() && current < self.players.len() {
+            // Teams are only even with every seat filled
+            return Err(GameError::NotEnoughPlayers { needed: self.max_players, current });
+        }
+
+        for seat in 0..self.players.len() {
+            self.deal_to(seat, INITIAL_HAND_SIZE);
+        }
+
+        // Add first card to discard pile
+        if let Some(card) = self.deck.pop() {
+            self.discard_pile.push(card);
+        }
+        self.deck_size = self.deck.len();
+
+        if let Some(clock) = self.clock {
+            for player in self.players.iter_mut().flatten() {
+                player.time_bank = Some(clock.initial_micros);
+            }
+        }
+
+        self.current_player_index = 0;
+        self.status = MatchStatus::InProgress;
+        self.turn_start_time = Some(now);
+        self.turn_number += 1;
+        Ok(())
+    }
+
+    /// Reject an action issued for an earlier (or later) turn
+    pub fn check_turn_number(&self, issued: u64) -> Result<(), GameError> {
+        if issued != self.turn_number {
+            return Err(GameError::StaleTurn { issued, current: self.turn_number });
+        }
+        Ok(())
+    }
+
+    /// Reject a repeated last-card call or challenge - they don't change the turn,
+    /// so they carry their own counter instead of the turn number
+    pub fn check_last_card_seq(&self, issued: u64) -> Result<(), GameError> {
+        if issued != self.last_card_seq {
+            return Err(GameError::StaleLastCard { issued, current: self.last_card_seq });
+        }
+        Ok(())
+    }
+
+    /// Play the card with `card_id` from `seat`'s hand, optionally guarded by
+    /// the top card the client saw - a stale action is rejected, never misapplied
+    pub fn play_card_by_id(
+        &mut self,
+        seat: usize,
+        card_id: u8,
+        chosen_suit: Option<CardSuit>,
+        expected_top_card_id: Option<u8>,
+        now: u64,
+    ) -> Result<PlayOutcome, GameError> {
+        self.play_cards_by_id(seat, &[card_id], chosen_suit, expected_top_card_id, now)
+    }
+
+    /// Play several same-value cards from `seat`'s hand in one move, in the given order
+    pub fn play_cards_by_id(
+        &mut self,
+        seat: usize,
+        card_ids: &[u8],
+        chosen_suit: Option<CardSuit>,
+        expected_top_card_id: Option<u8>,
+        now: u64,
+    ) -> Result<PlayOutcome, GameError> {
+        self.check_turn(seat)?;
+        if let Some(expected) = expected_top_card_id {
+            if self.discard_pile.last().map(|card| card.id) != Some(expected) {
+                return Err(GameError::StaleTopCard);
+            }
+        }
+        let card_indices = card_ids.iter()
+            .map(|card_id| self.hand_index_of(seat, *card_id).ok_or(GameError::CardNotInHand))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.play_cards(seat, &card_indices, chosen_suit, now)
+    }
+
+    /// Position of the card with `card_id` in `seat`'s hand
+    pub fn hand_index_of(&self, seat: usize, card_id: u8) -> Option<usize> {
+        self.players.get(seat)?.as_ref()?
+            .hand.iter()
+            .position(|card| card.id == card_id)
+    }
+
+    /// Play the card at `card_index` from `seat`'s hand
+    pub fn play_card(
+        &mut self,
+        seat: usize,
+        card_index: usize,
+        chosen_suit: Option<CardSuit>,
+        now: u64,
+    ) -> Result<PlayOutcome, GameError> {
+        self.play_cards(seat, &[card_index], chosen_suit, now)
+    }
+
+    /// Play the cards at `card_indices` from `seat`'s hand in one move.
+    /// They must share a value and the first must be playable; the last ends up on top
+    /// and each card's own effect fires in turn (two Pick Twos stack four, while a
+    /// Star 5 adds nothing to the Pick Three of another 5).
+    pub fn play_cards(
+        &mut self,
+        seat: usize,
+        card_indices: &[usize],
+        chosen_suit: Option<CardSuit>,
+        now: u64,
+    ) -> Result<PlayOutcome, GameError> {
+        let Some((&first_index, rest)) = card_indices.split_first() else {
+            return Err(GameError::NoCards);
+        };
+        let card = self.current_hand_card(seat, first_index)?;
+        let mut more_cards = Vec::with_capacity(rest.len());
+        for (position, &card_index) in rest.iter().enumerate() {
+            if card_index == first_index || rest[..position].contains(&card_index) {
+                return Err(GameError::DuplicateCard);
+            }
+            let other = self.current_hand_card(seat, card_index)?;
+            if other.value != card.value {
+                return Err(GameError::MixedValues);
+            }
+            more_cards.push(other);
+        }
+        if !self.is_playable(&card) {
+            return Err(GameError::InvalidPlay);
+        }
+        if card.value == WHOT_VALUE && !chosen_suit.is_some_and(|suit| suit != CardSuit::Whot) {
+            return Err(GameError::SuitRequired);
+        }
+        self.check_clock(now)?;
+        self.charge_clock(seat, now);
+        self.turn_number += 1;
+
+        // A pending penalty must be drawn first unless this card blocks it
+        let answers_penalty = self.blocks_penalty(&card);
+        let mut penalty_drawn = 0;
+        if self.pending_penalty.is_some() && !answers_penalty {
+            let total = self.take_pending_penalty();
+            penalty_drawn = self.deal_to(seat, total);
+        }
+
+        // Remove from the back so earlier indices stay valid
+        let mut removed: Vec<usize> = card_indices.to_vec();
+        removed.sort_unstable_by(|a, b| b.cmp(a));
+        let player = self.players[seat].as_mut().ok_or(GameError::InvalidSeat)?;
+        let player_nickname = player.nickname.clone();
+        for card_index in removed {
+            player.hand.remove(card_index);
+        }
+        player.update_hand_size();
+        player.consecutive_timeouts = 0;
+        let hand_empty = player.hand.is_empty();
+        self.discard_pile.push(card.clone());
+        self.discard_pile.extend(more_cards.iter().cloned());
+
+        let effects: Vec<Option<SpecialEffect>> = std::iter::once(&card)
+            .chain(&more_cards)
+            .map(|played| self.effect_of(played, chosen_suit))
+            .collect();
+        // Reported effect: the first one that fires
+        let special_effect = effects.iter().flatten().next().cloned();
+
+        // Check win condition - the card's effect no longer matters
+        if hand_empty {
+            self.status = MatchStatus::Finished;
+            self.winner_index = Some(seat);
+            self.winning_team = self.team_of(seat);
+            return Ok(PlayOutcome {
+                card,
+                more_cards,
+                market_draws: Vec::new(),
+                announcement: Some(HandAnnouncement::CheckUp),
+                special_effect,
+                penalty_drawn,
+                next_seat: seat,
+                winner: Some(seat),
+            });
+        }
+
+        // WHOT sets the demand suit, any other card clears it
+        self.whot_demand = match chosen_suit {
+            Some(suit) if card.value == WHOT_VALUE => Some(WhotDemand { suit, seat, player_nickname }),
+            _ => None,
+        };
+
+        self.current_player_index = self.next_seat(seat);
+        self.turn_start_time = Some(now);
+
+        // Whot and Hold On answers settle the penalty instead of their usual effect
+        let mut effect_used = false;
+        if answers_penalty {
+            match card.value {
+                WHOT_VALUE => self.pending_penalty = None,
+                HOLD_ON_VALUE => {
+                    self.reflect_penalty(seat);
+                    effect_used = true;
+                }
+                _ => {}
+            }
+        }
+
+        // The answering card's own effect is spent on the answer
+        let mut market_draws = Vec::new();
+        for (position, effect) in effects.iter().enumerate() {
+            if position == 0 && effect_used {
+                continue;
+            }
+            if let Some(effect) = effect {
+                self.apply_special_effect(effect, seat, &mut market_draws);
+            }
+        }
+
+        let hand_size = self.players[seat].as_ref().map_or(0, |player| player.hand.len());
+        let announcement = (self.rules.semi_last_card && hand_size == 2).then_some(HandAnnouncement::SemiLastCard);
+
+        Ok(PlayOutcome {
+            card,
+            more_cards,
+            market_draws,
+            announcement,
+            special_effect,
+            penalty_drawn,
+            next_seat: self.current_player_index,
+            winner: None,
+        })
+    }
+
+    /// Draw for `seat`: one card plus any pending penalty
+    pub fn draw_card(&mut self, seat: usize, now: u64) -> Result<DrawOutcome, GameError> {
+        self.check_turn(seat)?;
+        self.check_clock(now)?;
+
+        let hand = &self.players[seat].as_ref().ok_or(GameError::InvalidSeat)?.hand;
+        if !self.draw_allowed(hand.iter().any(|card| self.is_playable(card))) {
+            return Err(GameError::MustPlay);
+        }
+        if self.deck.is_empty() {
+            self.turn_number += 1;
+            let winner = self.finish_on_card_count();
+            return Ok(DrawOutcome::DeckExhausted { winner });
+        }
+        self.charge_clock(seat, now);
+        self.turn_number += 1;
+
+        let penalty = self.take_pending_penalty();
+
+        let count = self.deal_to(seat, 1 + penalty);
+        if let Some(player) = self.players[seat].as_mut() {
+            player.consecutive_timeouts = 0;
+        }
+
+        self.current_player_index = self.next_seat(seat);
+        self.turn_start_time = Some(now);
+
+        Ok(DrawOutcome::Drawn {
+            count,
+            penalty,
+            next_seat: self.current_player_index,
+        })
+    }
+
+    /// Mark `seat` as having called last card
+    pub fn call_last_card(&mut self, seat: usize) -> Result<LastCardOutcome, GameError> {
+        if self.status != MatchStatus::InProgress {
+            return Err(GameError::NotInProgress);
+        }
+        if !self.is_active(seat) {
+            return Err(GameError::InvalidSeat);
+        }
+        let player = self.players[seat].as_mut().ok_or(GameError::InvalidSeat)?;
+        let hand_size = player.hand.len();
+        // Right or wrong, the call is settled - a repeat of it is stale
+        self.last_card_seq += 1;
+        if hand_size <= self.rules.last_card_window as usize {
+            player.called_last_card = true;
+            return Ok(LastCardOutcome { accepted: true, hand_size, penalty_cards: 0 });
+        }
+
+        // False call - nothing to announce yet
+        let penalty_cards = self.draw_with_recycle(seat, self.rules.false_call_penalty);
+        Ok(LastCardOutcome { accepted: false, hand_size, penalty_cards })
+    }
+
+    /// `challenger` calls out `target` for holding one card without calling last card.
+    /// Open until `target`'s next turn begins; a wrong call costs the challenger.
+    pub fn challenge_last_card(&mut self, challenger: usize, target: usize) -> Result<ChallengeOutcome, GameError> {
+        if self.status != MatchStatus::InProgress {
+            return Err(GameError::NotInProgress);
+        }
+        if !self.is_active(challenger) || !self.is_active(target) {
+            return Err(GameError::InvalidSeat);
+        }
+        if challenger == target {
+            return Err(GameError::SelfChallenge);
+        }
+        if target == self.current_player_index {
+            return Err(GameError::ChallengeWindowClosed);
+        }
+
+        let player = self.players[target].as_ref().ok_or(GameError::InvalidSeat)?;
+        // Upheld or not, the challenge is settled - a repeat of it is stale
+        self.last_card_seq += 1;
+        if player.hand.len() != 1 || player.called_last_card {
+            let penalty_cards = self.draw_with_recycle(challenger, self.rules.false_challenge_penalty);
+            return Ok(ChallengeOutcome { success: false, penalty_cards });
+        }
+
+        let penalty_cards = self.draw_with_recycle(target, CHALLENGE_PENALTY_CARDS);
+        Ok(ChallengeOutcome { success: true, penalty_cards })
+    }
+
+    /// Whether the current player's time bank has run out at `now`
+    pub fn clock_expired(&self, now: u64) -> bool {
+        self.clock.is_some() && self.check_clock(now).is_err()
+    }
+
+    /// Take `seat` out of the match; the last active player wins
+    pub fn forfeit(&mut self, seat: usize, now: u64) -> Result<ForfeitOutcome, GameError> {
+        if self.status != MatchStatus::InProgress {
+            return Err(GameError::NotInProgress);
+        }
+        if !self.is_active(seat) {
+            return Err(GameError::InvalidSeat);
+        }
+        self.turn_number += 1;
+        Ok(self.remove_seat(seat, now))
+    }
+
+    /// Expire the current turn - the penalty grows with each consecutive
+    /// timeout until the seat is removed for inactivity
+    pub fn timeout_turn(&mut self, now: u64) -> Result<TimeoutOutcome, GameError> {
+        if self.status != MatchStatus::InProgress {
+            return Err(GameError::NotInProgress);
+        }
+        let seat = self.current_player_index;
+        let player = self.players[seat].as_mut().ok_or(GameError::InvalidSeat)?;
+        player.consecutive_timeouts += 1;
+        let consecutive_timeouts = player.consecutive_timeouts;
+        self.turn_number += 1;
+
+        if consecutive_timeouts >= MAX_CONSECUTIVE_TIMEOUTS {
+            let policy = self.inactivity_policy;
+            let mut penalty_served = 0;
+            let mut cards_drawn = 0;
+            let (next_seat, winner) = match policy {
+                InactivityPolicy::Forfeit => {
+                    let outcome = self.remove_seat(seat, now);
+                    (outcome.next_seat, outcome.winner)
+                }
+                InactivityPolicy::ReplaceWithBot => {
+                    // The seat stays in the match, so it still owes the pending penalty
+                    penalty_served = self.take_pending_penalty();
+                    cards_drawn = self.draw_with_recycle(seat, penalty_served);
+                    if let Some(player) = self.players[seat].as_mut() {
+                        player.bot = Some(INACTIVITY_BOT);
+                        player.consecutive_timeouts = 0;
+                    }
+                    // The bot starts with the next turn of this seat
+                    self.current_player_index = self.next_seat(seat);
+                    self.turn_start_time = Some(now);
+                    (self.current_player_index, None)
+                }
+            };
+            return Ok(TimeoutOutcome {
+                seat,
+                cards_drawn,
+                penalty_served,
+                consecutive_timeouts,
+                removed: Some(policy),
+                next_seat,
+                winner,
+            });
+        }
+
+        // Like a voluntary draw the pending penalty is served, not passed on -
+        // turning the discard pile over when the deck can't cover it
+        let penalty_served = self.take_pending_penalty();
+        let cards_drawn = self.draw_with_recycle(seat, consecutive_timeouts + penalty_served);
+
+        self.current_player_index = self.next_seat(seat);
+        self.turn_start_time = Some(now);
+
+        Ok(TimeoutOutcome {
+            seat,
+            cards_drawn,
+            penalty_served,
+            consecutive_timeouts,
+            removed: None,
+            next_seat: self.current_player_index,
+            winner: None,
+        })
+    }
+
+    // ================================================================================
+    // INTERNAL HELPERS
+    // ================================================================================
+
+    /// Mark `seat` as out and pass the turn on if it was theirs
+    fn remove_seat(&mut self, seat: usize, now: u64) -> ForfeitOutcome {
+        if let Some(player) = self.players[seat].as_mut() {
+            player.forfeited = true;
+            if player.time_bank.is_some() {
+                player.time_bank = Some(0);
+            }
+        }
+
+        let remaining: Vec<usize> = (0..self.players.len())
+            .filter(|&index| self.is_active(index))
+            .collect();
+        // In team play the match is over once a single team is left at the table
+        let one_side_left = match remaining.first() {
+            Some(&first) if self.has_teams() => remaining.iter().all(|&index| self.team_of(index) == self.team_of(first)),
+            _ => remaining.len() == 1,
+        };
+        if let Some(&winner) = remaining.first().filter(|_| one_side_left) {
+            self.status = MatchStatus::Finished;
+            self.winner_index = Some(winner);
+            self.winning_team = self.team_of(winner);
+            return ForfeitOutcome { seat, next_seat: winner, winner: Some(winner) };
+        }
+
+        if seat == self.current_player_index {
+            // A penalty aimed at the leaving player lapses with them
+            self.pending_penalty = None;
+            self.current_player_index = self.next_seat(seat);
+            self.turn_start_time = Some(now);
+        }
+
+        ForfeitOutcome {
+            seat,
+            next_seat: self.current_player_index,
+            winner: None,
+        }
+    }
+
+    fn check_turn(&self, seat: usize) -> Result<(), GameError> {
+        if self.status != MatchStatus::InProgress {
+            return Err(GameError::NotInProgress);
+        }
+        if seat != self.current_player_index {
+            return Err(GameError::NotYourTurn);
+        }
+        Ok(())
+    }
+
+    /// Reject the current player's action once their time bank is spent
+    fn check_clock(&self, now: u64) -> Result<(), GameError> {
+        if self.clock.is_none() {
+            return Ok(());
+        }
+        let elapsed = now.saturating_sub(self.turn_start_time.unwrap_or(now));
+        if elapsed > self.turn_allotment() {
+            return Err(GameError::OutOfTime);
+        }
+        Ok(())
+    }
+
+    /// Deduct the turn's thinking time from `seat`'s bank and add the increment
+    fn charge_clock(&mut self, seat: usize, now: u64) {
+        let Some(clock) = self.clock else {
+            return;
+        };
+        let elapsed = now.saturating_sub(self.turn_start_time.unwrap_or(now));
+        if let Some(bank) = self.players[seat].as_mut().and_then(|player| player.time_bank.as_mut()) {
+            *bank = bank.saturating_sub(elapsed) + clock.increment_micros;
+        }
+    }
+
+    fn current_hand_card(&self, seat: usize, card_index: usize) -> Result<Card, GameError> {
+        self.check_turn(seat)?;
+        self.players[seat].as_ref()
+            .ok_or(GameError::InvalidSeat)?
+            .hand.get(card_index)
+            .cloned()
+            .ok_or(GameError::InvalidCardIndex)
+    }
+
+    /// Move up to `count` cards from the deck into `seat`'s hand, returning how many moved
+    fn deal_to(&mut self, seat: usize, count: u8) -> u8 {
+        let mut dealt = 0;
+        if let Some(player) = self.players.get_mut(seat).and_then(|p| p.as_mut()) {
+            for _ in 0..count {
+                match self.deck.pop() {
+                    Some(card) => {
+                        player.hand.push(card);
+                        dealt += 1;
+                    }
+                    None => break,
+                }
+            }
+            if dealt > 0 {
+                // A growing hand always takes back a last-card call
+                player.called_last_card = false;
+            }
+            player.update_hand_size();
+        }
+        self.deck_size = self.deck.len();
+        dealt
+    }
+
+    /// Deal `count` cards, recycling the discard pile first if the deck is short
+    fn draw_with_recycle(&mut self, seat: usize, count: u8) -> u8 {
+        if self.deck.len() < count as usize {
+            self.recycle_discard_pile();
+        }
+        self.deal_to(seat, count)
+    }
+
+    /// Turn the discard pile (minus the top card) back into the deck
+    fn recycle_discard_pile(&mut self) {
+        if self.discard_pile.len() <= 1 {
+            return;
+        }
+        let top_card = self.discard_pile.pop();
+        self.deck.append(&mut self.discard_pile);
+        let len = self.deck.len();
+        for i in (1..len).rev() {
+            let j = (i * 7 + 11) % (i + 1);
+            self.deck.swap(i, j);
+        }
+        if let Some(card) = top_card {
+            self.discard_pile.push(card);
+        }
+        self.deck_size = self.deck.len();
+    }
+
+    /// End the match on fewest cards held; `None` when several players tie.
+    /// In team play the team holding the fewest cards in total wins, and its
+    /// member holding the fewest cards is reported as the winner.
+    fn finish_on_card_count(&mut self) -> Option<usize> {
+        let leaders = self.card_count_leaders();
+        let first_team = leaders.first().and_then(|&seat| self.team_of(seat));
+        let one_side = match self.has_teams() {
+            true => leaders.iter().all(|&seat| self.team_of(seat) == first_team),
+            false => leaders.len() == 1,
+        };
+
+        self.status = MatchStatus::Finished;
+        self.winning_team = None;
+        self.winner_index = None;
+        if one_side {
+            self.winning_team = first_team;
+            self.winner_index = leaders.iter()
+                .copied()
+                .min_by_key(|&seat| self.players[seat].as_ref().map_or(0, |player| player.hand.len()));
+        }
+        self.winner_index
+    }
+
+    /// Apply the effect of a special card; the turn has already passed to the next seat
+    fn apply_special_effect(&mut self, effect: &SpecialEffect, played_by: usize, market_draws: &mut Vec<MarketDraw>) {
+        match effect {
+            SpecialEffect::PickTwo => self.raise_penalty(2, played_by),
+
+            SpecialEffect::PickThree => self.raise_penalty(3, played_by),
+
+            SpecialEffect::HoldOn => {
+                self.current_player_index = match self.rules.hold_on {
+                    // Skip the player after the one whose turn it now is
+                    HoldOnRule::SkipNext => self.next_seat(self.current_player_index),
+                    HoldOnRule::PlayAgain => played_by,
+                };
+            }
+
+            SpecialEffect::GeneralMarket => self.general_market(played_by, market_draws),
+
+            SpecialEffect::WhotPlayed { .. } => {
+                // No additional action - suit change already handled in play_card
+            }
+
+            SpecialEffect::Suspension => {
+                // Next player sits this turn out
+                self.current_player_index = self.next_seat(self.current_player_index);
+            }
+
+            SpecialEffect::Reverse => {
+                // Turn passes the other way from the player who reversed
+                self.direction = self.direction.reversed();
+                self.current_player_index = self.next_seat(played_by);
+            }
+        }
+    }
+
+    /// Start a penalty with the card on top of the discard pile, or stack onto the pending one
+    fn raise_penalty(&mut self, cards: u8, played_by: usize) {
+        let Some(source) = self.discard_pile.last().cloned() else {
+            return;
+        };
+        match self.pending_penalty.as_mut() {
+            Some(penalty) => {
+                penalty.total += cards;
+                penalty.source = source;
+                penalty.raised_by = played_by;
+            }
+            None => self.pending_penalty = Some(PendingPenalty { source, raised_by: played_by, total: cards }),
+        }
+    }
+
+    /// Hold On answer: the penalty goes back to whoever raised it (if still in the match)
+    fn reflect_penalty(&mut self, reflected_by: usize) {
+        let source = self.discard_pile.last().cloned();
+        let Some(penalty) = self.pending_penalty.as_mut() else {
+            return;
+        };
+        let target = penalty.raised_by;
+        penalty.raised_by = reflected_by;
+        if let Some(source) = source {
+            penalty.source = source;
+        }
+        if self.is_active(target) {
+            self.current_player_index = target;
+        }
+    }
+
+    /// Clear the pending penalty, returning the cards it was worth
+    fn take_pending_penalty(&mut self) -> u8 {
+        self.pending_penalty.take().map_or(0, |penalty| penalty.total)
+    }
+
+    /// Every other active player draws `market_draw` cards, going round from the next seat;
+    /// repeated markets in one move add up in `market_draws`
+    fn general_market(&mut self, played_by: usize, market_draws: &mut Vec<MarketDraw>) {
+        let mut seat = self.next_seat(played_by);
+        while seat != played_by {
+            let Some(player) = self.players[seat].as_ref() else { break };
+            let player_nickname = player.nickname.clone();
+            let defended = self.rules.market_defence
+                && player.hand.iter().any(|card| card.value == MARKET_VALUE);
+            let cards_drawn = if defended { 0 } else { self.draw_with_recycle(seat, self.rules.market_draw) };
+
+            match market_draws.iter_mut().find(|draw| draw.seat == seat) {
+                Some(draw) => {
+                    draw.cards_drawn += cards_drawn;
+                    draw.defended |= defended;
+                }
+                None => market_draws.push(MarketDraw { seat, player_nickname, cards_drawn, defended }),
+            }
+            seat = self.next_seat(seat);
+        }
+    }
+}
+
+/// Lobby shuffled with `seed` and a player in every `true` seat - seat `i` sits on chain `[i; 32]` as `Reserved(i + 1)`
+#[cfg(test)]
+pub(crate) fn seated_match(seats: &[bool], seed: u64) -> MatchData {
+    use linera_sdk::linera_base_types::{ChainId, CryptoHash};
+
+    let mut match_data = MatchData::new_lobby(seats.len() as u8, seed);
+    for (seat, seated) in seats.iter().enumerate() {
+        if *seated {
+            match_data.players[seat] = Some(Player::new(
+                ChainId(CryptoHash::from([seat as u8; 32])),
+                AccountOwner::Reserved(seat as u8 + 1),
+                format!("P{}", seat + 1),
+            ));
+        }
+    }
+    match_data
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::{ChainId, CryptoHash};
+
+    use super::*;
+    use crate::RuleSet;
+
+    /// Started match with a player in every `true` seat and a Circle 3 on the discard pile
+    fn started_match(seats: &[bool], rules: RuleSet) -> MatchData {
+        let mut match_data = seated_match(seats, 7);
+        match_data.rules = rules;
+        match_data.start(0).expect("match starts");
+        match_data.discard_pile = vec![Card::new(CardSuit::Circle, 3)];
+        match_data.pending_penalty = None;
+        match_data
+    }
+
+    /// Put `card` in `seat`'s hand and return its index
+    fn give(match_data: &mut MatchData, seat: usize, card: Card) -> usize {
+        let hand = &mut match_data.players[seat].as_mut().expect("seated").hand;
+        hand.push(card);
+        hand.len() - 1
+    }
+
+    /// `from` to move holding a Circle 1 on a Circle 3
+    fn hold_on_position(seats: &[bool], from: usize, hold_on: HoldOnRule) -> (MatchData, usize) {
+        let mut match_data = started_match(seats, RuleSet { hold_on, ..RuleSet::default() });
+        match_data.current_player_index = from;
+        let card_index = give(&mut match_data, from, Card::new(CardSuit::Circle, 1));
+        (match_data, card_index)
+    }
+
+    #[test]
+    fn hold_on_across_player_counts() {
+        use HoldOnRule::{PlayAgain, SkipNext};
+        let x = true;
+        let o = false;
+        // (seats, player holding on, rule, seat to move next)
+        let cases: &[(&[bool], usize, HoldOnRule, usize)] = &[
+            (&[x, x], 0, SkipNext, 0),
+            (&[x, x], 1, PlayAgain, 1),
+            (&[x, x, x], 0, SkipNext, 2),
+            (&[x, x, x], 0, PlayAgain, 0),
+            (&[x, x, x], 2, SkipNext, 1),
+            (&[x, o, x], 0, SkipNext, 0),
+            (&[x, x, x, x], 3, SkipNext, 1),
+            (&[x, x, x, x], 3, PlayAgain, 3),
+            (&[x, o, x, x], 3, SkipNext, 2),
+            (&[x, o, x, x, o], 0, SkipNext, 3),
+            (&[x, o, x, x, o], 3, SkipNext, 2),
+            (&[x, x, x, x, x], 4, PlayAgain, 4),
+            (&[x, x, o, x, o, x], 5, SkipNext, 1),
+            (&[x, x, o, x, o, x], 1, SkipNext, 5),
+            (&[x, x, x, x, x, x], 2, PlayAgain, 2),
+            (&[x, o, o, x, o, o], 3, SkipNext, 3),
+            (&[x, o, o, x, o, o], 3, PlayAgain, 3),
+        ];
+
+        for &(seats, from, rule, expected) in cases {
+            let (mut match_data, card_index) = hold_on_position(seats, from, rule);
+            let outcome = match_data.play_card(from, card_index, None, 1).expect("hold on is playable");
+            assert_eq!(outcome.special_effect, Some(SpecialEffect::HoldOn));
+            assert_eq!(
+                outcome.next_seat, expected,
+                "{} seats {:?}, P{} holds on with {:?}", seats.len(), seats, from + 1, rule,
+            );
+        }
+    }
+
+    #[test]
+    fn answering_a_pick_two() {
+        let standard = RuleSet::default();
+        let cross = RuleSet { cross_stacking: true, ..standard };
+        let whot = RuleSet { whot_defence: true, ..standard };
+        let reflect = RuleSet { hold_on_reflects: true, ..standard };
+        let circle = |value| Card::new(CardSuit::Circle, value);
+        let triangle = |value| Card::new(CardSuit::Triangle, value);
+        let whot_card = Card::new(CardSuit::Whot, WHOT_VALUE);
+        // P1 plays a Circle 2, P2 answers with `answer`:
+        // (rules, answer, penalty P2 draws first, penalty pending afterwards, seat to move next)
+        let cases = [
+            (standard, triangle(2), 0, 4, 2),
+            (standard, circle(5), 2, 3, 2),
+            (standard, whot_card.clone(), 2, 0, 2),
+            (standard, circle(1), 2, 0, 0),
+            (cross, circle(5), 0, 5, 2),
+            (cross, triangle(5), 0, 5, 2),
+            (cross, triangle(2), 0, 4, 2),
+            (whot, whot_card.clone(), 0, 0, 2),
+            (whot, triangle(2), 0, 4, 2),
+            (reflect, circle(1), 0, 2, 0),
+            (reflect, triangle(1), 0, 2, 0),
+            (reflect, circle(5), 2, 3, 2),
+        ];
+
+        for (rules, answer, expected_drawn, expected_pending, expected_next) in cases {
+            let mut match_data = started_match(&[true, true, true], rules);
+            let pick_two = give(&mut match_data, 0, circle(2));
+            match_data.play_card(0, pick_two, None, 1).expect("pick two is playable");
+            assert_eq!(match_data.pending_total(), 2);
+
+            let answer_index = give(&mut match_data, 1, answer.clone());
+            let chosen_suit = (answer.value == WHOT_VALUE).then_some(CardSuit::Circle);
+            let outcome = match_data.play_card(1, answer_index, chosen_suit, 2)
+                .unwrap_or_else(|error| panic!("{:?} with {:?}: {}", answer, rules, error));
+            let context = format!("{:?} with {:?}", answer, rules);
+            assert_eq!(outcome.penalty_drawn, expected_drawn, "{}", context);
+            assert_eq!(match_data.pending_total(), expected_pending, "{}", context);
+            assert_eq!(outcome.next_seat, expected_next, "{}", context);
+        }
+    }
+
+    #[test]
+    fn multi_card_plays_apply_each_cards_effect() {
+        let plays = [
+            (vec![Card::new(CardSuit::Star, 5), Card::new(CardSuit::Circle, 5)], 3),
+            (vec![Card::new(CardSuit::Circle, 5), Card::new(CardSuit::Star, 5)], 3),
+            (vec![Card::new(CardSuit::Circle, 5), Card::new(CardSuit::Square, 5)], 6),
+            (vec![Card::new(CardSuit::Circle, 2), Card::new(CardSuit::Square, 2), Card::new(CardSuit::Star, 2)], 6),
+        ];
+        for (cards, stacked) in plays {
+            let mut match_data = started_match(&[true, true], RuleSet::default());
+            match_data.discard_pile = vec![Card::new(CardSuit::Triangle, cards[0].value)];
+            match_data.current_player_index = 0;
+            let indices: Vec<usize> = cards.iter().map(|card| give(&mut match_data, 0, card.clone())).collect();
+            let outcome = match_data.play_cards(0, &indices, None, 1).expect("same-value cards are playable");
+            assert_eq!(match_data.pending_total(), stacked, "{:?}", cards);
+            assert!(outcome.special_effect.is_some());
+            assert_eq!(outcome.next_seat, 1);
+        }
+    }
+
+    #[test]
+    fn reflected_penalty_can_bounce_back() {
+        let rules = RuleSet { hold_on_reflects: true, ..RuleSet::default() };
+        let mut match_data = started_match(&[true, true, true], rules);
+        let pick_three = give(&mut match_data, 0, Card::new(CardSuit::Circle, 5));
+        match_data.play_card(0, pick_three, None, 1).expect("pick three is playable");
+
+        let hold_on = give(&mut match_data, 1, Card::new(CardSuit::Cross, 1));
+        match_data.play_card(1, hold_on, None, 2).expect("P2 reflects");
+        assert_eq!(match_data.current_player_index, 0);
+
+        let hold_on = give(&mut match_data, 0, Card::new(CardSuit::Square, 1));
+        match_data.play_card(0, hold_on, None, 3).expect("P1 reflects back");
+        assert_eq!(match_data.current_player_index, 1);
+        let penalty = match_data.pending_penalty.clone().expect("penalty still pending");
+        assert_eq!((penalty.total, penalty.raised_by), (3, 0));
+    }
+
+    #[test]
+    fn cross_stacking_is_off_by_default() {
+        let mut match_data = started_match(&[true, true], RuleSet::default());
+        let pick_two = give(&mut match_data, 0, Card::new(CardSuit::Circle, 2));
+        match_data.play_card(0, pick_two, None, 1).expect("pick two is playable");
+
+        // Triangle 5 neither follows the Circle 2 nor may answer it
+        let pick_three = give(&mut match_data, 1, Card::new(CardSuit::Triangle, 5));
+        assert_eq!(match_data.play_card(1, pick_three, None, 2).unwrap_err(), GameError::InvalidPlay);
+    }
+
+    #[test]
+    fn last_card_calls_follow_the_window() {
+        let mut match_data = started_match(&[true, true], RuleSet::default());
+        let hand = &mut match_data.players[1].as_mut().expect("seated").hand;
+        hand.truncate(3);
+
+        // Three cards is outside the default window of two
+        let turn_number = match_data.turn_number;
+        let last_card_seq = match_data.last_card_seq;
+        let outcome = match_data.call_last_card(1).expect("call is processed");
+        assert!(!outcome.accepted);
+        assert_eq!(outcome.penalty_cards, 2);
+        assert_eq!(match_data.players[1].as_ref().expect("seated").hand.len(), 5);
+        // A double-clicked call is stale instead of drawing the penalty twice,
+        // while the current player's move stays valid
+        assert_eq!(match_data.check_last_card_seq(last_card_seq), Err(GameError::StaleLastCard { issued: last_card_seq, current: last_card_seq + 1 }));
+        assert_eq!(match_data.check_turn_number(turn_number), Ok(()));
+
+        match_data.players[1].as_mut().expect("seated").hand.truncate(2);
+        assert!(match_data.call_last_card(1).expect("call is processed").accepted);
+        assert!(match_data.players[1].as_ref().expect("seated").called_last_card);
+
+        // Any card added to the hand takes the call back
+        match_data.deal_to(1, 1);
+        assert!(!match_data.players[1].as_ref().expect("seated").called_last_card);
+    }
+
+    #[test]
+    fn challenges_need_an_opponent_and_an_open_window() {
+        let mut match_data = started_match(&[true, true, true], RuleSet::default());
+        match_data.players[1].as_mut().expect("seated").hand.truncate(1);
+        match_data.current_player_index = 2;
+
+        assert_eq!(match_data.challenge_last_card(1, 1).unwrap_err(), GameError::SelfChallenge);
+        assert_eq!(match_data.challenge_last_card(0, 2).unwrap_err(), GameError::ChallengeWindowClosed);
+
+        // P3 holds six cards - a false challenge costs P1 a card
+        match_data.current_player_index = 1;
+        let outcome = match_data.challenge_last_card(0, 2).expect("challenge is processed");
+        assert!(!outcome.success);
+        assert_eq!(match_data.players[0].as_ref().expect("seated").hand.len(), INITIAL_HAND_SIZE as usize + 1);
+
+        // P2 never called last card, but their turn has begun
+        assert_eq!(match_data.challenge_last_card(0, 1).unwrap_err(), GameError::ChallengeWindowClosed);
+        match_data.current_player_index = 2;
+        let turn_number = match_data.turn_number;
+        let last_card_seq = match_data.last_card_seq;
+        let outcome = match_data.challenge_last_card(0, 1).expect("challenge is processed");
+        assert!(outcome.success);
+        assert_eq!(match_data.players[1].as_ref().expect("seated").hand.len(), 1 + CHALLENGE_PENALTY_CARDS as usize);
+        // A replayed challenge is stale rather than a false one, and P3 may still move
+        assert_eq!(match_data.check_last_card_seq(last_card_seq), Err(GameError::StaleLastCard { issued: last_card_seq, current: last_card_seq + 1 }));
+        assert_eq!(match_data.check_turn_number(turn_number), Ok(()));
+    }
+
+    #[test]
+    fn teams_win_together() {
+        let mut lobby = MatchData::new_lobby(4, 1);
+        assert!(matches!(lobby.set_teams(vec![0, 1, 0]), Err(GameError::InvalidTeams(_))));
+        assert!(matches!(lobby.set_teams(vec![0, 0, 0, 1]), Err(GameError::InvalidTeams(_))));
+        assert!(matches!(lobby.set_teams(vec![1, 1, 1, 1]), Err(GameError::InvalidTeams(_))));
+        lobby.set_teams(vec![0, 1, 0, 1]).expect("partners sit opposite");
+
+        // P3 goes out - P1 wins with them
+        let mut match_data = started_match(&[true; 4], RuleSet::default());
+        match_data.teams = vec![0, 1, 0, 1];
+        match_data.current_player_index = 2;
+        match_data.players[2].as_mut().expect("seated").hand.clear();
+        let card_index = give(&mut match_data, 2, Card::new(CardSuit::Circle, 7));
+        let outcome = match_data.play_card(2, card_index, None, 1).expect("card is played");
+        assert_eq!(outcome.winner, Some(2));
+        assert_eq!(match_data.winning_team, Some(0));
+
+        // Deck runs out: P2 and P4 hold 6 cards between them against 10
+        let mut match_data = started_match(&[true; 4], RuleSet::default());
+        match_data.teams = vec![0, 1, 0, 1];
+        match_data.deck.clear();
+        for (seat, size) in [5, 1, 5, 5].into_iter().enumerate() {
+            match_data.players[seat].as_mut().expect("seated").hand = vec![Card::new(CardSuit::Triangle, 4); size];
+        }
+        assert!(matches!(match_data.draw_card(0, 1), Ok(DrawOutcome::DeckExhausted { winner: Some(1) })));
+        assert_eq!(match_data.winning_team, Some(1));
+        assert_eq!(match_data.scores_by_team()[1].cards_held, 6);
+
+        // The match goes on until a whole team has left
+        let mut match_data = started_match(&[true; 4], RuleSet::default());
+        match_data.teams = vec![0, 1, 0, 1];
+        assert_eq!(match_data.forfeit(1, 1).expect("seat leaves").winner, None);
+        assert_eq!(match_data.forfeit(3, 2).expect("seat leaves").winner, Some(0));
+        assert_eq!(match_data.winning_team, Some(0));
+    }
+
+    #[test]
+    fn only_timeouts_turn_the_discard_pile_over() {
+        let pile = vec![
+            Card::new(CardSuit::Star, 4),
+            Card::new(CardSuit::Square, 11),
+            Card::new(CardSuit::Circle, 3),
+        ];
+        let pick_two = PendingPenalty { source: Card::new(CardSuit::Circle, 2), raised_by: 1, total: 2 };
+
+        // Voluntary draw with a Pick Two pending: only what's left in the deck, the pile stays put
+        let mut match_data = started_match(&[true, true], RuleSet::default());
+        match_data.deck = vec![Card::new(CardSuit::Cross, 13)];
+        match_data.discard_pile = pile.clone();
+        match_data.pending_penalty = Some(pick_two.clone());
+        match_data.players[0].as_mut().expect("seated").hand = vec![Card::new(CardSuit::Triangle, 7)];
+        let outcome = match_data.draw_card(0, 1).expect("draw is served");
+        assert!(matches!(outcome, DrawOutcome::Drawn { count: 1, penalty: 2, next_seat: 1 }));
+        assert_eq!(match_data.discard_pile, pile);
+
+        // With the deck gone and nothing to play the match ends, however big the pile
+        match_data.current_player_index = 0;
+        assert!(matches!(match_data.draw_card(0, 2), Ok(DrawOutcome::DeckExhausted { .. })));
+        assert_eq!(match_data.status, MatchStatus::Finished);
+
+        // A timeout serves its penalty from the recycled pile - both cards under the top one
+        let mut match_data = started_match(&[true, true], RuleSet::default());
+        match_data.deck.clear();
+        match_data.discard_pile = pile;
+        match_data.pending_penalty = Some(pick_two);
+        let outcome = match_data.timeout_turn(1).expect("timeout is served");
+        assert_eq!((outcome.cards_drawn, outcome.penalty_served), (2, 2));
+        assert_eq!(match_data.discard_pile, vec![Card::new(CardSuit::Circle, 3)]);
+    }
+
+    #[test]
+    fn public_view_hides_hands_deck_and_seed() {
+        let mut match_data = started_match(&[true, true, true], RuleSet::default());
+        match_data.seed = 1_712_345_678_901_234;
+        match_data.spectators = vec![ChainId(CryptoHash::from([9; 32]))];
+
+        let view = match_data.public_view();
+        assert_eq!(view.seed, 0);
+        assert!(view.deck.is_empty());
+        assert!(view.spectators.is_empty());
+        for (seat, player) in view.players.iter().enumerate() {
+            let player = player.as_ref().expect("seated");
+            assert!(player.hand.is_empty());
+            // Hand sizes stay public
+            assert_eq!(player.hand_size, match_data.players[seat].as_ref().expect("seated").hand_size);
+        }
+        assert_eq!(view.discard_pile, match_data.discard_pile);
+    }
+
+    #[test]
+    fn legal_moves_and_draw_card_agree_on_an_empty_deck() {
+        let mut match_data = started_match(&[true, true], RuleSet::default());
+        match_data.deck.clear();
+        match_data.discard_pile = vec![
+            Card::new(CardSuit::Star, 4),
+            Card::new(CardSuit::Square, 11),
+            Card::new(CardSuit::Circle, 3),
+        ];
+
+        // Holding a Circle 9: playing it is the only move
+        match_data.players[0].as_mut().expect("seated").hand = vec![Card::new(CardSuit::Circle, 9), Card::new(CardSuit::Star, 12)];
+        let moves = match_data.legal_moves(0);
+        assert_eq!((moves.can_draw, moves.must_draw), (false, false));
+        assert_eq!(match_data.clone().draw_card(0, 1).unwrap_err(), GameError::MustPlay);
+
+        // Nothing fits: drawing is allowed and ends the match on card count
+        match_data.players[0].as_mut().expect("seated").hand = vec![Card::new(CardSuit::Star, 12)];
+        let moves = match_data.legal_moves(0);
+        assert_eq!((moves.can_draw, moves.must_draw), (true, true));
+        assert!(matches!(match_data.draw_card(0, 1), Ok(DrawOutcome::DeckExhausted { .. })));
+    }
+}
@@ -1,4 +1,4 @@
-use async_graphql::{Enum, Request, Response, SimpleObject};
+use async_graphql::{ComplexObject, Enum, InputObject, Request, Response, SimpleObject};
 use linera_sdk::{
     abi::{ContractAbi, ServiceAbi},
     graphql::GraphQLMutationRoot,
@@ -6,6 +6,19 @@ use linera_sdk::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::bot::BotDifficulty;
+use crate::chat::{ChatEntry, Emote, Reaction};
+use crate::escrow::{Escrow, Payout, Stake};
+use crate::tournament::TournamentSettings;
+
+pub mod bot;
+pub mod chat;
+pub mod escrow;
+pub mod game;
+pub mod notation;
+pub mod replay;
+pub mod tournament;
+
 /// The Linot application ABI
 pub struct LinotAbi;
 
@@ -42,39 +55,100 @@ pub enum Operation {
     CreateMatch { 
         max_players: u8,
         nickname: String,
+        options: Option<MatchOptions>,  // Defaults for every setting left out
     },
     
     /// Join existing match (sends message to Play chain)
     JoinMatch {
         play_chain_id: ChainId,
         nickname: String,
+        stake: Option<Stake>,  // Entry fee paid with the request - must match the match's stake
     },
     
     /// Start match (host only)
     StartMatch,
     
+    /// Call off a match that hasn't started, refunding every entry fee (host only)
+    CancelMatch,
+    
+    /// Seat a bot in an empty slot (host only, before the match starts)
+    AddBot {
+        seat: usize,
+        difficulty: BotDifficulty,
+    },
+    
+    /// Split the seats into teams (host only, before the match starts)
+    SetTeams {
+        teams: Vec<u8>,  // Team of each seat, numbered from 0 - empty turns team play off
+    },
+    
     /// Play a card
     PlayCard {
-        card_index: usize,
+        card_id: u8,
         chosen_suit: Option<CardSuit>,  // For Whot cards
+        expected_top_card_id: Option<u8>,  // Reject if the top card changed since the client looked
+        turn_number: u64,               // MatchData.turn_number the action was issued for
+    },
+    
+    /// Play several cards of the same value in one move (first one must fit the top card)
+    PlayCards {
+        card_ids: Vec<u8>,              // In play order - the last one ends up on top
+        chosen_suit: Option<CardSuit>,  // For Whot cards
+        expected_top_card_id: Option<u8>,
+        turn_number: u64,
     },
     
     /// Draw a card
-    DrawCard,
+    DrawCard {
+        turn_number: u64,
+    },
     
     /// Call "Last Card"
-    CallLastCard,
+    CallLastCard {
+        last_card_seq: u64,             // MatchData.last_card_seq the call was issued for
+    },
     
     /// Challenge another player for not calling last card
     ChallengeLastCard {
         player_index: usize,
+        last_card_seq: u64,             // MatchData.last_card_seq the challenge was issued for
     },
     
     /// Check if current turn has timed out (called periodically)
     CheckTimeout,
     
-    /// Leave match
+    /// Leave match - gives up the seat (and any entry fee) while the lobby is still open
     LeaveMatch,
+    
+    /// Watch a match without taking a seat (subscribes to game events only)
+    Spectate { play_chain_id: ChainId },
+    
+    /// Stop watching the spectated match
+    StopSpectating,
+    
+    /// Post a message to the table chat (players and spectators)
+    SendChat { text: String },
+    
+    /// Mute or unmute a chain in the table chat (host only)
+    MuteChat { chain_id: ChainId, muted: bool },
+    
+    /// Send a predefined emote to the table, or at one seat
+    React { emote: Emote, target_seat: Option<usize> },
+    
+    /// Open a tournament with this chain as its coordinator
+    CreateTournament {
+        name: String,
+        settings: TournamentSettings,
+    },
+    
+    /// Sign up for the tournament run from `tournament_chain_id`
+    RegisterForTournament {
+        tournament_chain_id: ChainId,
+        nickname: String,
+    },
+    
+    /// Close registration and seat the first round (organizer, on the coordinator chain)
+    StartTournament,
 }
 
 // ============================================================================
@@ -88,6 +162,7 @@ pub enum UserStatus {
     WaitingToJoin,  // Waiting for join confirmation from PLAY_CHAIN
     InMatch,
     WaitingForPlayers,
+    Spectating,     // Watching a match without a seat
 }
 
 impl Default for UserStatus {
@@ -107,6 +182,7 @@ pub enum Message {
         creator_owner: AccountOwner,
         max_players: u8,
         nickname: String,
+        options: MatchOptions,  // Any stake is already paid into the PLAY_CHAIN escrow
     },
     
     /// PLAY_CHAIN -> USER_CHAIN: Result of create match request
@@ -120,6 +196,7 @@ pub enum Message {
         player_owner: AccountOwner,
         player_chain: ChainId,
         nickname: String,
+        stake: Option<Stake>,  // Already paid into the PLAY_CHAIN escrow - refunded if the join fails
     },
     
     /// PLAY_CHAIN -> USER_CHAIN: Confirm join was successful (triggers subscribe)
@@ -133,27 +210,124 @@ pub enum Message {
         player_owner: AccountOwner,
     },
     
+    /// USER_CHAIN -> PLAY_CHAIN: Host calls the match off before it starts
+    CancelMatchAction {
+        player_owner: AccountOwner,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Player gives up their seat before the match starts
+    LeaveLobbyAction {
+        player_owner: AccountOwner,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Host seats a bot in an empty slot
+    AddBotAction {
+        player_owner: AccountOwner,
+        seat: usize,
+        difficulty: BotDifficulty,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Host assigns seats to teams
+    SetTeamsAction {
+        player_owner: AccountOwner,
+        teams: Vec<u8>,
+    },
+    
     /// USER_CHAIN -> PLAY_CHAIN: Player action: play card
     PlayCardAction {
         player_owner: AccountOwner,
-        card_index: usize,
+        card_id: u8,
+        chosen_suit: Option<CardSuit>,
+        expected_top_card_id: Option<u8>,
+        turn_number: u64,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Player action: play several same-value cards
+    PlayCardsAction {
+        player_owner: AccountOwner,
+        card_ids: Vec<u8>,
         chosen_suit: Option<CardSuit>,
+        expected_top_card_id: Option<u8>,
+        turn_number: u64,
     },
     
     /// USER_CHAIN -> PLAY_CHAIN: Player action: draw card  
     DrawCardAction {
         player_owner: AccountOwner,
+        turn_number: u64,
     },
     
     /// USER_CHAIN -> PLAY_CHAIN: Player calls last card
     CallLastCardAction {
         player_owner: AccountOwner,
+        last_card_seq: u64,
     },
     
     /// USER_CHAIN -> PLAY_CHAIN: Player challenges another for not calling last card
     ChallengeLastCardAction {
         challenger_owner: AccountOwner,
         challenged_player_index: usize,
+        last_card_seq: u64,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Request to watch the match without a seat
+    RequestSpectate {
+        spectator_chain: ChainId,
+    },
+    
+    /// PLAY_CHAIN -> USER_CHAIN: Spectate accepted (triggers subscribe)
+    /// Carries a public snapshot of the match; `None` means rejected
+    SpectateConfirmed {
+        play_chain_id: ChainId,
+        snapshot: Option<Box<MatchData>>,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Spectator stopped watching
+    StopSpectatingAction {
+        spectator_chain: ChainId,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Table chat message (sender is the origin chain)
+    ChatAction {
+        nickname: Option<String>,  // Shown for spectators; seated players go by their seat
+        text: String,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Host mutes or unmutes a chain in the chat
+    MuteChatAction {
+        player_owner: AccountOwner,
+        chain_id: ChainId,
+        muted: bool,
+    },
+    
+    /// USER_CHAIN -> PLAY_CHAIN: Emote (sender is the origin chain)
+    ReactAction {
+        nickname: Option<String>,
+        emote: Emote,
+        target_seat: Option<usize>,
+    },
+    
+    /// USER_CHAIN -> TOURNAMENT_CHAIN: Sign up for the tournament
+    RegisterEntrant {
+        owner: AccountOwner,
+        player_chain: ChainId,
+        nickname: String,
+    },
+    
+    /// TOURNAMENT_CHAIN -> USER_CHAIN: Registration accepted or rejected
+    RegistrationResult {
+        tournament_chain_id: ChainId,
+        success: bool,
+    },
+    
+    /// TOURNAMENT_CHAIN -> new PLAY_CHAIN: Seat these players and start the table's match
+    OpenTable {
+        tournament_chain_id: ChainId,
+        round: u8,
+        table: usize,
+        players: Vec<Player>,  // In seat order
+        time_control: TimeControl,
+        rules: RuleSet,
     },
 }
 
@@ -176,6 +350,17 @@ pub enum GameEvent {
         player_count: usize,
     },
     
+    /// Player gave up their seat before the match started
+    PlayerLeftLobby {
+        nickname: String,
+        player_count: usize,
+    },
+    
+    /// Host changed the team of each seat (empty when team play was turned off)
+    TeamsAssigned {
+        teams: Vec<u8>,
+    },
+    
     /// Match started
     MatchStarted {
         players: Vec<String>,
@@ -189,6 +374,22 @@ pub enum GameEvent {
         card: Card,
         next_player: String,
         special_effect: Option<SpecialEffect>,
+        whot_demand: Option<WhotDemand>,  // Demand in force after the play
+    },
+    
+    /// Several same-value cards played in one move
+    CardsPlayed {
+        player_nickname: String,
+        cards: Vec<Card>,                 // In play order - the last one is the new top card
+        next_player: String,
+        special_effect: Option<SpecialEffect>,  // First effect that fired - each card applies its own
+        whot_demand: Option<WhotDemand>,
+    },
+    
+    /// General Market settled - who drew how many
+    GeneralMarketResolved {
+        player_nickname: String,  // Who played the 14
+        draws: Vec<MarketDraw>,
     },
     
     /// Cards drawn
@@ -203,22 +404,55 @@ pub enum GameEvent {
         player_nickname: String,
     },
     
+    /// Last card called with too many cards - the caller drew a penalty
+    LastCardRejected {
+        player_nickname: String,
+        hand_size: usize,
+        penalty_cards: u8,
+    },
+    
+    /// Automatic announcement after a play
+    HandAnnounced {
+        player_nickname: String,
+        announcement: HandAnnouncement,
+    },
+    
     /// Match ended
     MatchEnded {
         winner: String,
         winner_index: usize,
+        winning_team: Option<u8>,  // Set in team play - the whole team wins
     },
     
     /// Match won
     MatchWon {
         winner_nickname: String,
         winner_index: usize,
+        winning_team: Option<u8>,
     },
     
-    /// Challenge penalty applied
-    ChallengePenalty {
-        challenged_player: String,
-        penalty_cards: u8,
+    /// Last-card challenge settled - `penalty` cards went to the challenged
+    /// player on success, to the challenger otherwise
+    ChallengeResolved {
+        challenger: String,
+        challenged: String,
+        success: bool,
+        penalty: u8,
+    },
+    
+    /// Host called the match off before it started
+    MatchCancelled {
+        host: String,
+    },
+    
+    /// Pot of a staked match handed to the winner(s)
+    PotPaidOut {
+        payouts: Vec<Payout>,
+    },
+    
+    /// Entry fees of a match that never started sent back
+    EntryFeesRefunded {
+        refunds: Vec<Payout>,
     },
     
     /// Turn started with timer
@@ -236,7 +470,53 @@ pub enum GameEvent {
     /// Turn timed out
     TurnTimeout {
         player_nickname: String,
-        auto_drawn: bool,
+        cards_drawn: u8,      // Includes the served penalty
+        penalty_served: u8,   // Pending Pick Two / Pick Three cards served
+    },
+    
+    /// Seat timed out too many turns in a row
+    PlayerRemovedForInactivity {
+        player_nickname: String,
+        replaced_by_bot: bool,
+        next_player: String,
+    },
+    
+    /// Player's time bank ran out - they are out of the match
+    PlayerOutOfTime {
+        player_nickname: String,
+        next_player: String,
+    },
+    
+    /// Spectator joined or left
+    SpectatorCountChanged {
+        spectator_count: usize,
+    },
+    
+    /// Message posted to the table chat
+    ChatMessage {
+        entry: ChatEntry,
+    },
+    
+    /// Emote sent at the table (not kept in any history)
+    Reaction {
+        reaction: Reaction,
+    },
+    
+    /// Player signed up for the tournament (TOURNAMENT_CHAIN)
+    EntrantRegistered {
+        nickname: String,
+        entrant_count: usize,
+    },
+    
+    /// A tournament round has been seated (TOURNAMENT_CHAIN)
+    TournamentRoundStarted {
+        round: u8,
+        table_count: usize,
+    },
+    
+    /// Tournament is over (TOURNAMENT_CHAIN)
+    TournamentFinished {
+        champion: Option<String>,
     },
 }
 
@@ -247,6 +527,7 @@ pub enum GameEvent {
 /// Card definition
 #[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq)]
 pub struct Card {
+    pub id: u8,  // Position in the standard deck - stable for the whole match
     pub suit: CardSuit,
     pub value: u8,
 }
@@ -263,13 +544,55 @@ pub enum CardSuit {
 }
 
 /// Special card effects
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum SpecialEffect {
     PickTwo,
     PickThree,
     HoldOn,
     GeneralMarket,
     WhotPlayed { chosen_suit: CardSuit },
+    Suspension,  // Next player loses their turn (RuleSet::suspension)
+    Reverse,     // Play direction flips (RuleSet::reverse)
+}
+
+/// Announcements the PLAY_CHAIN makes on a player's behalf
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum HandAnnouncement {
+    SemiLastCard,  // Down to two cards (RuleSet::semi_last_card)
+    CheckUp,       // Played their last card
+}
+
+/// Pick Two / Pick Three penalty building up on the table
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq)]
+pub struct PendingPenalty {
+    pub source: Card,      // Card that last raised (or reflected) the penalty
+    pub raised_by: usize,  // Seat that played `source` - a reflected penalty goes back here
+    pub total: u8,         // Cards to draw
+}
+
+/// What one player got from a General Market
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct MarketDraw {
+    pub seat: usize,
+    pub player_nickname: String,
+    pub cards_drawn: u8,  // Can fall short of the rule when deck and discard pile run dry
+    pub defended: bool,   // Showed a 14 instead of drawing (RuleSet::market_defence)
+}
+
+/// Cards a team still holds - the deck-exhausted tie-break in team play
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct TeamScore {
+    pub team: u8,
+    pub seats: Vec<usize>,
+    pub cards_held: usize,  // Summed over the members still in the match
+}
+
+/// Suit named with the last Whot card; cleared by the next card played
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct WhotDemand {
+    pub suit: CardSuit,
+    pub seat: usize,              // Seat that played the Whot
+    pub player_nickname: String,
 }
 
 /// Player in match
@@ -282,6 +605,10 @@ pub struct Player {
     pub hand: Vec<Card>,
     pub hand_size: usize,  // Public info
     pub called_last_card: bool,
+    pub bot: Option<BotDifficulty>,  // Set for bot seats played by the PLAY_CHAIN
+    pub time_bank: Option<u64>,      // Remaining clock time (micros) when the match uses a clock
+    pub forfeited: bool,             // Out of the match - skipped in turn order
+    pub consecutive_timeouts: u8,    // Reset by any move the player makes
 }
 
 impl Player {
@@ -293,9 +620,28 @@ impl Player {
             hand: Vec::new(),
             hand_size: 0,
             called_last_card: false,
+            bot: None,
+            time_bank: None,
+            forfeited: false,
+            consecutive_timeouts: 0,
         }
     }
     
+    /// Bot seated on the PLAY_CHAIN; the reserved owner is unique per seat
+    pub fn new_bot(chain_id: ChainId, seat: usize, difficulty: BotDifficulty) -> Self {
+        let mut player = Self::new(
+            chain_id,
+            AccountOwner::Reserved(seat as u8 + 1),
+            format!("Bot {} ({:?})", seat + 1, difficulty),
+        );
+        player.bot = Some(difficulty);
+        player
+    }
+    
+    pub fn is_bot(&self) -> bool {
+        self.bot.is_some()
+    }
+    
     pub fn update_hand_size(&mut self) {
         self.hand_size = self.hand.len();
     }
@@ -303,22 +649,38 @@ impl Player {
 
 /// Match state
 #[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct MatchData {
     pub players: Vec<Option<Player>>,
     #[graphql(skip)]
     pub deck: Vec<Card>,
     pub discard_pile: Vec<Card>,
     pub current_player_index: usize,
+    pub direction: PlayDirection,       // Order turns pass in
     pub status: MatchStatus,
     pub winner_index: Option<usize>,
+    pub teams: Vec<u8>,                 // Team of each seat; empty when it's every player for themselves
+    pub winning_team: Option<u8>,       // Set when a team won
     pub max_players: u8,
     pub deck_size: usize,  // Public info
-    pub pending_draw_stack: u8,        // Total cards to draw (for stacking)
-    #[graphql(skip)]
-    pub pending_draw_type: Option<u8>, // Value of stacking card (2 or 5)
-    pub active_demand_suit: Option<CardSuit>, // Suit chosen by last WHOT card played
+    pub pending_penalty: Option<PendingPenalty>,  // Pick Two / Pick Three waiting to be served or answered
+    pub whot_demand: Option<WhotDemand>, // Set by the last WHOT card played
     pub turn_start_time: Option<u64>,   // When current turn started (micros)
     pub turn_duration: u64,             // Turn duration in micros
+    pub current_turn_allotment: u64,    // Time the current player got at TurnStarted (their time bank on a clock)
+    pub time_control: TimeControl,      // Preset turn_duration was taken from
+    pub clock: Option<ClockSettings>,   // Set when players play on time banks
+    pub inactivity_policy: InactivityPolicy,  // What happens to a seat that keeps timing out
+    pub rules: RuleSet,                 // House rules picked by the host
+    pub escrow: Option<Escrow>,         // Entry fees held for a staked match
+    pub spectator_count: usize,         // Public info
+    #[graphql(skip)]
+    pub spectators: Vec<ChainId>,       // USER_CHAINs watching without a seat
+    pub match_id: u64,                  // Increments for every match hosted on the PLAY_CHAIN
+    pub turn_number: u64,               // Increments on every accepted move - guards late messages
+    pub last_card_seq: u64,             // Increments on every settled last-card call and challenge - guards repeats of them
+    #[graphql(skip)]
+    pub seed: u64,                      // Deck shuffle seed (secret until replay)
 }
 
 impl Default for MatchData {
@@ -328,15 +690,185 @@ impl Default for MatchData {
             deck: Vec::new(),
             discard_pile: Vec::new(),
             current_player_index: 0,
+            direction: PlayDirection::Clockwise,
             status: MatchStatus::Waiting,
             winner_index: None,
+            teams: Vec::new(),
+            winning_team: None,
             max_players: 2,
             deck_size: 0,
-            pending_draw_stack: 0,
-            pending_draw_type: None,
-            active_demand_suit: None,
+            pending_penalty: None,
+            whot_demand: None,
             turn_start_time: None,
             turn_duration: crate::TURN_TIMEOUT_MICROS,
+            current_turn_allotment: 0,
+            time_control: TimeControl::Normal,
+            clock: None,
+            inactivity_policy: InactivityPolicy::Forfeit,
+            rules: RuleSet::default(),
+            escrow: None,
+            spectator_count: 0,
+            spectators: Vec::new(),
+            match_id: 0,
+            turn_number: 0,
+            last_card_seq: 0,
+            seed: 0,
+        }
+    }
+}
+
+#[ComplexObject]
+impl MatchData {
+    /// Total cards the current player draws unless they answer the penalty
+    async fn pending_draw_stack(&self) -> u8 {
+        self.pending_total()
+    }
+
+    /// Cards held per team (empty outside team play)
+    async fn team_scores(&self) -> Vec<TeamScore> {
+        self.scores_by_team()
+    }
+}
+
+impl MatchData {
+    /// Copy of the match that is safe to hand to spectators:
+    /// no hands, no deck, no spectator chain list (sizes are kept)
+    pub fn public_view(&self) -> MatchData {
+        let mut view = self.clone();
+        for player in view.players.iter_mut().flatten() {
+            player.hand.clear();
+        }
+        view.deck.clear();
+        view.spectators.clear();
+        // The seed rebuilds the shuffle, and with it every hand and the draw order
+        view.seed = 0;
+        view
+    }
+
+    /// Time the current player has for this turn: their time bank on a clock, turn_duration otherwise
+    pub fn turn_allotment(&self) -> u64 {
+        if self.clock.is_none() {
+            return self.turn_duration;
+        }
+        self.players.get(self.current_player_index)
+            .and_then(|p| p.as_ref())
+            .and_then(|player| player.time_bank)
+            .unwrap_or(0)
+    }
+
+    /// Elapsed turn time after which a TurnWarning is emitted
+    pub fn turn_warning_micros(&self) -> u64 {
+        let (numerator, denominator) = TURN_WARNING_FRACTION;
+        self.turn_allotment() / denominator * numerator
+    }
+}
+
+/// Turn timer preset picked by the host at CreateMatch
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum TimeControl {
+    Blitz,           // 30 seconds per turn
+    #[default]
+    Normal,          // 3 minutes per turn
+    Correspondence,  // 24 hours per turn
+}
+
+impl TimeControl {
+    pub fn turn_duration_micros(&self) -> u64 {
+        match self {
+            TimeControl::Blitz => 30_000_000,
+            TimeControl::Normal => TURN_TIMEOUT_MICROS,
+            TimeControl::Correspondence => 86_400_000_000,
+        }
+    }
+}
+
+/// What happens to a seat after too many consecutive timeouts
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum InactivityPolicy {
+    #[default]
+    Forfeit,         // The player is out of the match
+    ReplaceWithBot,  // A bot plays the seat from then on
+}
+
+/// Settings picked by the host at CreateMatch
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, InputObject, PartialEq, Eq)]
+pub struct MatchOptions {
+    pub time_control: Option<TimeControl>,  // Defaults to Normal
+    pub clock: Option<ClockSettings>,       // Chess-clock time banks instead of a flat turn timer
+    pub inactivity_policy: Option<InactivityPolicy>,  // Defaults to Forfeit
+    pub rules: Option<RuleSet>,             // Defaults to RuleSet::default()
+    pub stake: Option<Stake>,               // Entry fee, paid by the host on creation
+}
+
+/// Chess-clock settings: every player starts with `initial_micros` and
+/// gets `increment_micros` back after each move
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SimpleObject, InputObject, PartialEq, Eq)]
+#[graphql(input_name = "ClockSettingsInput")]
+pub struct ClockSettings {
+    pub initial_micros: u64,
+    pub increment_micros: u64,
+}
+
+/// House rules picked by the host at CreateMatch
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SimpleObject, InputObject, PartialEq, Eq)]
+#[graphql(input_name = "RuleSetInput")]
+pub struct RuleSet {
+    pub whot_on_whot: bool,  // A Whot may be played while a Whot demand is active
+    pub suspension: bool,    // 8 suspends the next player
+    pub reverse: bool,       // 10 reverses the play direction
+    pub hold_on: HoldOnRule, // What a 1 (Hold On) does
+    pub market_draw: u8,     // Cards each other player draws on General Market (14)
+    pub market_defence: bool, // Holding a 14 excuses a player from General Market
+    pub cross_stacking: bool, // Pick Two answers Pick Three and the other way round
+    pub whot_defence: bool,   // A Whot cancels a pending penalty
+    pub hold_on_reflects: bool, // Hold On sends a pending penalty back to whoever raised it
+    pub last_card_window: u8, // Largest hand a last-card call is accepted with (2 = when going to one card)
+    pub false_call_penalty: u8, // Cards drawn for calling last card outside the window
+    pub semi_last_card: bool, // Announce players who get down to two cards
+    pub false_challenge_penalty: u8, // Cards a challenger draws when the challenge fails
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            whot_on_whot: true,
+            suspension: false,
+            reverse: false,
+            hold_on: HoldOnRule::SkipNext,
+            market_draw: 1,
+            market_defence: false,
+            cross_stacking: false,
+            whot_defence: false,
+            hold_on_reflects: false,
+            last_card_window: 2,
+            false_call_penalty: 2,
+            semi_last_card: false,
+            false_challenge_penalty: 1,
+        }
+    }
+}
+
+/// Hold On (1) variants
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum HoldOnRule {
+    #[default]
+    SkipNext,   // The next player loses their turn
+    PlayAgain,  // The player who held on moves again, whatever the table size
+}
+
+/// Order turns pass in around the table
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum PlayDirection {
+    #[default]
+    Clockwise,      // Seat 0, 1, 2, ...
+    Anticlockwise,  // Seat 0, n-1, n-2, ...
+}
+
+impl PlayDirection {
+    pub fn reversed(self) -> Self {
+        match self {
+            PlayDirection::Clockwise => PlayDirection::Anticlockwise,
+            PlayDirection::Anticlockwise => PlayDirection::Clockwise,
         }
     }
 }
@@ -357,5 +889,5 @@ pub const GAME_STREAM_NAME: &[u8] = b"linot_game_events";
 pub const INITIAL_HAND_SIZE: u8 = 6;
 pub const MAX_PLAYERS: u8 = 6;
 pub const MIN_PLAYERS: u8 = 2;
-pub const TURN_TIMEOUT_MICROS: u64 = 180_000_000;  // 3 minutes
-pub const TURN_WARNING_MICROS: u64 = 120_000_000;  // 2 minutes
+pub const TURN_TIMEOUT_MICROS: u64 = 180_000_000;  // 3 minutes (Normal time control)
+pub const TURN_WARNING_FRACTION: (u64, u64) = (2, 3);  // Warn after 2/3 of the turn
@@ -0,0 +1,605 @@
+//! Portable text notation for finished Whot games.
+//!
+//! A record is a few `[Tag value]` header lines followed by one move per line:
+//!
+//! ```text
+//! [Match 3]
+//! [Seed 1712345678901234]
+//! [Players "Ada" "Bo" -]
+//! [Rules standard]
+//!
+//! P1 START
+//! P1 TRI7
+//! P2 CIR2 !PICK2
+//! P1 DRAW 3
+//! P2 WHOT→CIRCLE
+//! P1 CRO1 +2 !HOLD
+//! P2 SQU2/TRI2 !PICK2
+//! P2 TIMEOUT 1
+//! P1 LAST
+//! P2 CHALLENGE P1 +2
+//! ```
+//!
+//! Seats are written `P1..P6` (seat index + 1), empty seats as `-`. `[Teams]` is
+//! only written for team play and gives each seat's team, counted from 1. Cards are a
+//! suit code (`CIR`, `TRI`, `CRO`, `SQU`, `STA`) plus value, or `WHOT`; cards
+//! played together are joined with `/`. `+n` is
+//! the number of penalty cards drawn as part of the move and `!EFFECT` names the
+//! special effect that fired. `WHOT>CIRCLE` is accepted as an ASCII form of the
+//! arrow, and lines starting with `#` are comments. See `docs/GAME_NOTATION.md`.
+
+use std::fmt::Write as _;
+
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::game::{suit_values, WHOT_VALUE};
+use crate::replay::{MatchHeader, MoveKind, MoveRecord};
+use crate::{Card, CardSuit, HoldOnRule, RuleSet, SpecialEffect};
+
+/// Rules tag written when the match used the default rules
+pub const STANDARD_RULES: &str = "standard";
+
+/// `[Rules]` tag for `rules`: `standard`, or the changed options as `name=value,...`
+pub fn rules_tag(rules: &RuleSet) -> String {
+    let standard = RuleSet::default();
+    let mut changed = Vec::new();
+    if rules.whot_on_whot != standard.whot_on_whot {
+        changed.push(format!("whot-on-whot={}", on_off(rules.whot_on_whot)));
+    }
+    if rules.suspension != standard.suspension {
+        changed.push(format!("suspension={}", on_off(rules.suspension)));
+    }
+    if rules.reverse != standard.reverse {
+        changed.push(format!("reverse={}", on_off(rules.reverse)));
+    }
+    if rules.hold_on != standard.hold_on {
+        let variant = match rules.hold_on {
+            HoldOnRule::SkipNext => "skip-next",
+            HoldOnRule::PlayAgain => "play-again",
+        };
+        changed.push(format!("hold-on={}", variant));
+    }
+    if rules.market_draw != standard.market_draw {
+        changed.push(format!("market-draw={}", rules.market_draw));
+    }
+    if rules.market_defence != standard.market_defence {
+        changed.push(format!("market-defence={}", on_off(rules.market_defence)));
+    }
+    if rules.cross_stacking != standard.cross_stacking {
+        changed.push(format!("cross-stacking={}", on_off(rules.cross_stacking)));
+    }
+    if rules.whot_defence != standard.whot_defence {
+        changed.push(format!("whot-defence={}", on_off(rules.whot_defence)));
+    }
+    if rules.hold_on_reflects != standard.hold_on_reflects {
+        changed.push(format!("hold-on-reflects={}", on_off(rules.hold_on_reflects)));
+    }
+    if rules.last_card_window != standard.last_card_window {
+        changed.push(format!("last-card-window={}", rules.last_card_window));
+    }
+    if rules.false_call_penalty != standard.false_call_penalty {
+        changed.push(format!("false-call-penalty={}", rules.false_call_penalty));
+    }
+    if rules.semi_last_card != standard.semi_last_card {
+        changed.push(format!("semi-last-card={}", on_off(rules.semi_last_card)));
+    }
+    if rules.false_challenge_penalty != standard.false_challenge_penalty {
+        changed.push(format!("false-challenge-penalty={}", rules.false_challenge_penalty));
+    }
+    if changed.is_empty() {
+        STANDARD_RULES.to_string()
+    } else {
+        changed.join(",")
+    }
+}
+
+/// How to write the Whot suit arrow
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// `WHOT→CIRCLE`
+    Unicode,
+    /// `WHOT>CIRCLE`, for tools that only handle ASCII
+    Ascii,
+}
+
+/// A whole game in notation form
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub match_id: u64,
+    pub seed: u64,
+    pub players: Vec<Option<String>>,   // Nickname per seat
+    pub rules: String,
+    pub teams: Vec<u8>,                 // Team per seat; empty outside team play
+    pub moves: Vec<NotatedMove>,
+}
+
+/// One line of the move list
+#[derive(Clone, Debug, PartialEq)]
+pub struct NotatedMove {
+    pub seat: usize,
+    pub action: NotatedAction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NotatedAction {
+    Start,
+    Play {
+        card: Card,
+        more_cards: Vec<Card>,          // Same-value cards dropped in the same move
+        chosen_suit: Option<CardSuit>,
+        penalty_drawn: u8,
+        effect: Option<SpecialEffect>,
+    },
+    Draw { count: u8 },
+    Timeout { count: u8 },
+    LastCard { penalty: u8 },
+    Challenge { target: usize, penalty: u8, upheld: bool },
+    Forfeit,
+}
+
+/// Why a record could not be parsed
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum NotationError {
+    #[error("line {line}: malformed header")]
+    BadHeader { line: usize },
+    #[error("missing [{0}] header")]
+    MissingHeader(&'static str),
+    #[error("line {line}: malformed move `{text}`")]
+    BadMove { line: usize, text: String },
+    #[error("line {line}: unknown card `{text}`")]
+    UnknownCard { line: usize, text: String },
+}
+
+impl GameRecord {
+    /// Build a record from a match header and its move log
+    pub fn from_log(header: &MatchHeader, rules: String, log: &[MoveRecord]) -> Self {
+        Self {
+            match_id: header.match_id,
+            seed: header.seed,
+            players: header.players.iter()
+                .map(|p| p.as_ref().map(|player| player.nickname.clone()))
+                .collect(),
+            rules,
+            teams: header.teams.clone(),
+            moves: log.iter().map(NotatedMove::from_record).collect(),
+        }
+    }
+
+    /// Write the record in text notation
+    pub fn to_notation(&self, format: RecordFormat) -> String {
+        let mut out = String::new();
+        let players: Vec<String> = self.players.iter()
+            .map(|p| match p {
+                Some(name) => quote(name),
+                None => "-".to_string(),
+            })
+            .collect();
+
+        let _ = writeln!(out, "[Match {}]", self.match_id);
+        let _ = writeln!(out, "[Seed {}]", self.seed);
+        let _ = writeln!(out, "[Players {}]", players.join(" "));
+        let _ = writeln!(out, "[Rules {}]", self.rules);
+        if !self.teams.is_empty() {
+            let teams: Vec<String> = self.teams.iter().map(|team| (team + 1).to_string()).collect();
+            let _ = writeln!(out, "[Teams {}]", teams.join(" "));
+        }
+        out.push('\n');
+
+        for notated in &self.moves {
+            let _ = writeln!(out, "{}", notated.to_notation(format));
+        }
+        out
+    }
+
+    /// Parse a record written by `to_notation` (either format)
+    pub fn parse(text: &str) -> Result<Self, NotationError> {
+        let mut match_id = None;
+        let mut seed = None;
+        let mut players = None;
+        let mut rules = None;
+        let mut teams = Vec::new();
+        let mut moves = Vec::new();
+
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if let Some(tag) = trimmed.strip_prefix('[') {
+                let tag = tag.strip_suffix(']').ok_or(NotationError::BadHeader { line })?;
+                let (name, value) = tag.split_once(' ').ok_or(NotationError::BadHeader { line })?;
+                let bad_header = |_| NotationError::BadHeader { line };
+                match name {
+                    "Match" => match_id = Some(value.parse::<u64>().map_err(bad_header)?),
+                    "Seed" => seed = Some(value.parse::<u64>().map_err(bad_header)?),
+                    "Players" => players = Some(parse_players(value).ok_or(NotationError::BadHeader { line })?),
+                    "Rules" => rules = Some(value.to_string()),
+                    "Teams" => teams = parse_teams(value).ok_or(NotationError::BadHeader { line })?,
+                    _ => return Err(NotationError::BadHeader { line }),
+                }
+                continue;
+            }
+
+            moves.push(NotatedMove::parse(trimmed, line)?);
+        }
+
+        Ok(Self {
+            match_id: match_id.ok_or(NotationError::MissingHeader("Match"))?,
+            seed: seed.ok_or(NotationError::MissingHeader("Seed"))?,
+            players: players.ok_or(NotationError::MissingHeader("Players"))?,
+            rules: rules.unwrap_or_else(|| STANDARD_RULES.to_string()),
+            teams,
+            moves,
+        })
+    }
+}
+
+impl NotatedMove {
+    pub fn from_record(record: &MoveRecord) -> Self {
+        let action = match record.kind {
+            MoveKind::Start => NotatedAction::Start,
+            MoveKind::Play => NotatedAction::Play {
+                // Notation names cards by suit and value only
+                card: record.card.as_ref()
+                    .map(|card| Card::new(card.suit, card.value))
+                    .unwrap_or(Card::new(CardSuit::Whot, WHOT_VALUE)),
+                more_cards: record.more_cards.iter()
+                    .map(|card| Card::new(card.suit, card.value))
+                    .collect(),
+                chosen_suit: record.chosen_suit,
+                penalty_drawn: record.cards_drawn,
+                effect: record.effect.clone(),
+            },
+            MoveKind::Draw => NotatedAction::Draw { count: record.cards_drawn },
+            MoveKind::Timeout => NotatedAction::Timeout { count: record.cards_drawn },
+            MoveKind::CallLastCard => NotatedAction::LastCard { penalty: record.cards_drawn },
+            MoveKind::Challenge | MoveKind::FalseChallenge => NotatedAction::Challenge {
+                target: record.target_seat.unwrap_or(record.seat),
+                penalty: record.cards_drawn,
+                upheld: record.kind == MoveKind::Challenge,
+            },
+            MoveKind::Forfeit => NotatedAction::Forfeit,
+        };
+        Self { seat: record.seat, action }
+    }
+
+    pub fn to_notation(&self, format: RecordFormat) -> String {
+        let seat = seat_code(self.seat);
+        match &self.action {
+            NotatedAction::Start => format!("{} START", seat),
+            NotatedAction::Play { card, more_cards, chosen_suit, penalty_drawn, effect } => {
+                let mut line = format!("{} {}", seat, card_code(card));
+                for more in more_cards {
+                    let _ = write!(line, "/{}", card_code(more));
+                }
+                if let Some(suit) = chosen_suit {
+                    let arrow = match format {
+                        RecordFormat::Unicode => '→',
+                        RecordFormat::Ascii => '>',
+                    };
+                    let _ = write!(line, "{}{}", arrow, suit_name(*suit));
+                }
+                if *penalty_drawn > 0 {
+                    let _ = write!(line, " +{}", penalty_drawn);
+                }
+                // Whot effects are already spelled out by the arrow
+                if let Some(name) = effect.as_ref().and_then(effect_code) {
+                    let _ = write!(line, " !{}", name);
+                }
+                line
+            }
+            NotatedAction::Draw { count } => format!("{} DRAW {}", seat, count),
+            NotatedAction::Timeout { count } => format!("{} TIMEOUT {}", seat, count),
+            NotatedAction::LastCard { penalty: 0 } => format!("{} LAST", seat),
+            NotatedAction::LastCard { penalty } => format!("{} LAST +{}", seat, penalty),
+            NotatedAction::Challenge { target, penalty, upheld: true } => {
+                format!("{} CHALLENGE {} +{}", seat, seat_code(*target), penalty)
+            }
+            NotatedAction::Challenge { target, penalty, upheld: false } => {
+                format!("{} CHALLENGE {} FAILED +{}", seat, seat_code(*target), penalty)
+            }
+            NotatedAction::Forfeit => format!("{} FORFEIT", seat),
+        }
+    }
+
+    pub fn parse(text: &str, line: usize) -> Result<Self, NotationError> {
+        let bad_move = || NotationError::BadMove { line, text: text.to_string() };
+        let mut tokens = text.split_whitespace();
+        let seat = tokens.next().and_then(parse_seat).ok_or_else(bad_move)?;
+        let verb = tokens.next().ok_or_else(bad_move)?;
+        let rest: Vec<&str> = tokens.collect();
+
+        let count = |token: Option<&str>| token.and_then(|t| t.parse::<u8>().ok()).ok_or_else(bad_move);
+
+        let action = match verb {
+            "START" if rest.is_empty() => NotatedAction::Start,
+            "LAST" if rest.is_empty() => NotatedAction::LastCard { penalty: 0 },
+            "LAST" if rest.len() == 1 => NotatedAction::LastCard { penalty: count(rest[0].strip_prefix('+'))? },
+            "FORFEIT" if rest.is_empty() => NotatedAction::Forfeit,
+            "DRAW" if rest.len() == 1 => NotatedAction::Draw { count: count(rest.first().copied())? },
+            "TIMEOUT" if rest.len() == 1 => NotatedAction::Timeout { count: count(rest.first().copied())? },
+            "CHALLENGE" if rest.len() == 2 => NotatedAction::Challenge {
+                target: parse_seat(rest[0]).ok_or_else(bad_move)?,
+                penalty: count(rest[1].strip_prefix('+'))?,
+                upheld: true,
+            },
+            "CHALLENGE" if rest.len() == 3 && rest[1] == "FAILED" => NotatedAction::Challenge {
+                target: parse_seat(rest[0]).ok_or_else(bad_move)?,
+                penalty: count(rest[2].strip_prefix('+'))?,
+                upheld: false,
+            },
+            _ => {
+                let (card_text, suit_text) = match verb.split_once('→').or_else(|| verb.split_once('>')) {
+                    Some((card, suit)) => (card, Some(suit)),
+                    None => (verb, None),
+                };
+                let mut cards = card_text.split('/')
+                    .map(|code| parse_card(code).ok_or_else(|| NotationError::UnknownCard { line, text: code.to_string() }))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let card = cards.remove(0);
+                let more_cards = cards;
+                let chosen_suit = match suit_text {
+                    Some(name) => Some(parse_suit_name(name).ok_or_else(bad_move)?),
+                    None => None,
+                };
+
+                let mut penalty_drawn = 0;
+                let mut effect = None;
+                for token in rest {
+                    if let Some(n) = token.strip_prefix('+') {
+                        penalty_drawn = n.parse().map_err(|_| bad_move())?;
+                    } else if let Some(name) = token.strip_prefix('!') {
+                        effect = Some(parse_effect(name).ok_or_else(bad_move)?);
+                    } else {
+                        return Err(bad_move());
+                    }
+                }
+                // The arrow is the Whot effect
+                if effect.is_none() && card.value == WHOT_VALUE {
+                    effect = chosen_suit.map(|suit| SpecialEffect::WhotPlayed { chosen_suit: suit });
+                }
+
+                NotatedAction::Play { card, more_cards, chosen_suit, penalty_drawn, effect }
+            }
+        };
+
+        Ok(Self { seat, action })
+    }
+}
+
+// ================================================================================
+// TOKENS
+// ================================================================================
+
+fn seat_code(seat: usize) -> String {
+    format!("P{}", seat + 1)
+}
+
+fn parse_seat(text: &str) -> Option<usize> {
+    text.strip_prefix('P')?.parse::<usize>().ok()?.checked_sub(1)
+}
+
+/// `TRI7`, `CIR14`, `WHOT`
+pub fn card_code(card: &Card) -> String {
+    match card.suit {
+        CardSuit::Whot => "WHOT".to_string(),
+        suit => format!("{}{}", suit_code(suit), card.value),
+    }
+}
+
+/// Inverse of `card_code`
+pub fn parse_card(text: &str) -> Option<Card> {
+    if text == "WHOT" {
+        return Some(Card::new(CardSuit::Whot, WHOT_VALUE));
+    }
+    let (code, value) = (text.get(..3)?, text.get(3..)?);
+    let suit = [CardSuit::Circle, CardSuit::Triangle, CardSuit::Cross, CardSuit::Square, CardSuit::Star]
+        .into_iter()
+        .find(|suit| suit_code(*suit) == code)?;
+    // Card::new would give an out-of-deck value the id of a real card
+    let value = value.parse().ok().filter(|value| suit_values(suit).contains(value))?;
+    Some(Card::new(suit, value))
+}
+
+fn suit_code(suit: CardSuit) -> &'static str {
+    match suit {
+        CardSuit::Circle => "CIR",
+        CardSuit::Triangle => "TRI",
+        CardSuit::Cross => "CRO",
+        CardSuit::Square => "SQU",
+        CardSuit::Star => "STA",
+        CardSuit::Whot => "WHOT",
+    }
+}
+
+fn suit_name(suit: CardSuit) -> &'static str {
+    match suit {
+        CardSuit::Circle => "CIRCLE",
+        CardSuit::Triangle => "TRIANGLE",
+        CardSuit::Cross => "CROSS",
+        CardSuit::Square => "SQUARE",
+        CardSuit::Star => "STAR",
+        CardSuit::Whot => "WHOT",
+    }
+}
+
+fn parse_suit_name(text: &str) -> Option<CardSuit> {
+    [CardSuit::Circle, CardSuit::Triangle, CardSuit::Cross, CardSuit::Square, CardSuit::Star, CardSuit::Whot]
+        .into_iter()
+        .find(|suit| suit_name(*suit) == text)
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "on" } else { "off" }
+}
+
+fn effect_code(effect: &SpecialEffect) -> Option<&'static str> {
+    match effect {
+        SpecialEffect::PickTwo => Some("PICK2"),
+        SpecialEffect::PickThree => Some("PICK3"),
+        SpecialEffect::HoldOn => Some("HOLD"),
+        SpecialEffect::GeneralMarket => Some("MARKET"),
+        SpecialEffect::WhotPlayed { .. } => None,
+        SpecialEffect::Suspension => Some("SUSPEND"),
+        SpecialEffect::Reverse => Some("REVERSE"),
+    }
+}
+
+fn parse_effect(text: &str) -> Option<SpecialEffect> {
+    match text {
+        "PICK2" => Some(SpecialEffect::PickTwo),
+        "PICK3" => Some(SpecialEffect::PickThree),
+        "HOLD" => Some(SpecialEffect::HoldOn),
+        "MARKET" => Some(SpecialEffect::GeneralMarket),
+        "SUSPEND" => Some(SpecialEffect::Suspension),
+        "REVERSE" => Some(SpecialEffect::Reverse),
+        _ => None,
+    }
+}
+
+/// Quote a nickname, escaping `"` and `\`
+fn quote(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 2);
+    out.push('"');
+    for c in name.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
+
+/// `"Ada" - "Bo \"B\""` -> seats
+fn parse_players(text: &str) -> Option<Vec<Option<String>>> {
+    let mut players = Vec::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+        match chars.next() {
+            None => break,
+            Some('-') => players.push(None),
+            Some('"') => {
+                let mut name = String::new();
+                loop {
+                    match chars.next()? {
+                        '\\' => name.push(chars.next()?),
+                        '"' => break,
+                        c => name.push(c),
+                    }
+                }
+                players.push(Some(name));
+            }
+            Some(_) => return None,
+        }
+    }
+    Some(players)
+}
+
+/// `[Teams]` value: one team number per seat, counted from 1
+fn parse_teams(text: &str) -> Option<Vec<u8>> {
+    text.split_whitespace()
+        .map(|team| team.parse::<u8>().ok()?.checked_sub(1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(seat: usize, cards: &[Card], chosen_suit: Option<CardSuit>, penalty_drawn: u8, effect: Option<SpecialEffect>) -> NotatedMove {
+        NotatedMove {
+            seat,
+            action: NotatedAction::Play {
+                card: cards[0].clone(),
+                more_cards: cards[1..].to_vec(),
+                chosen_suit,
+                penalty_drawn,
+                effect,
+            },
+        }
+    }
+
+    fn record() -> GameRecord {
+        let rules = RuleSet {
+            suspension: true,
+            hold_on: HoldOnRule::PlayAgain,
+            ..RuleSet::default()
+        };
+        let at = |seat, action| NotatedMove { seat, action };
+        GameRecord {
+            match_id: 3,
+            seed: 1_712_345_678_901_234,
+            players: vec![Some("Ada".to_string()), Some("Bo \"B\"".to_string()), None, Some("Cy".to_string())],
+            rules: rules_tag(&rules),
+            teams: vec![0, 1, 0, 1],
+            moves: vec![
+                at(0, NotatedAction::Start),
+                play(0, &[Card::new(CardSuit::Triangle, 7)], None, 0, None),
+                play(1, &[Card::new(CardSuit::Circle, 2)], None, 0, Some(SpecialEffect::PickTwo)),
+                at(3, NotatedAction::Draw { count: 2 }),
+                play(0, &[Card::new(CardSuit::Whot, WHOT_VALUE)], Some(CardSuit::Circle), 0, Some(SpecialEffect::WhotPlayed { chosen_suit: CardSuit::Circle })),
+                play(1, &[Card::new(CardSuit::Square, 5), Card::new(CardSuit::Star, 5), Card::new(CardSuit::Circle, 5)], None, 0, Some(SpecialEffect::PickThree)),
+                play(3, &[Card::new(CardSuit::Cross, 1)], None, 3, Some(SpecialEffect::HoldOn)),
+                at(0, NotatedAction::Timeout { count: 1 }),
+                at(1, NotatedAction::LastCard { penalty: 0 }),
+                at(3, NotatedAction::LastCard { penalty: 2 }),
+                at(0, NotatedAction::Challenge { target: 1, penalty: 2, upheld: true }),
+                at(3, NotatedAction::Challenge { target: 0, penalty: 1, upheld: false }),
+                at(1, NotatedAction::Forfeit),
+            ],
+        }
+    }
+
+    #[test]
+    fn records_round_trip_in_both_formats() {
+        let record = record();
+        assert_eq!(record.rules, "suspension=on,hold-on=play-again");
+        for format in [RecordFormat::Unicode, RecordFormat::Ascii] {
+            let text = record.to_notation(format);
+            assert!(text.contains("[Teams 1 2 1 2]"));
+            assert!(text.contains("P2 SQU5/STA5/CIR5 !PICK3"));
+            assert_eq!(GameRecord::parse(&text), Ok(record.clone()), "{:?}:\n{}", format, text);
+        }
+        assert!(record.to_notation(RecordFormat::Unicode).contains("P1 WHOT→CIRCLE\n"));
+        assert!(record.to_notation(RecordFormat::Ascii).contains("P1 WHOT>CIRCLE\n"));
+
+        // Outside team play the tag is left out and parses back empty
+        let solo = GameRecord { teams: Vec::new(), rules: STANDARD_RULES.to_string(), ..record };
+        let text = solo.to_notation(RecordFormat::Ascii);
+        assert!(!text.contains("[Teams"));
+        assert_eq!(GameRecord::parse(&text), Ok(solo));
+    }
+
+    #[test]
+    fn malformed_tags_and_moves_are_rejected() {
+        let headers = "[Match 1]\n[Seed 9]\n[Players \"Ada\" \"Bo\"]\n";
+        assert!(GameRecord::parse(headers).is_ok());
+
+        assert_eq!(GameRecord::parse("[Match 1]\n[Seed nine]\n"), Err(NotationError::BadHeader { line: 2 }));
+        assert_eq!(GameRecord::parse("[Match 1\n"), Err(NotationError::BadHeader { line: 1 }));
+        assert_eq!(GameRecord::parse("[Opening Sicilian]\n"), Err(NotationError::BadHeader { line: 1 }));
+        assert_eq!(GameRecord::parse(&format!("{}[Teams 1 0]\n", headers)), Err(NotationError::BadHeader { line: 4 }));
+        assert_eq!(GameRecord::parse("[Match 1]\n[Seed 9]\n"), Err(NotationError::MissingHeader("Players")));
+
+        let with_move = |line: &str| GameRecord::parse(&format!("{}{}\n", headers, line));
+        assert_eq!(with_move("P1 BOX7"), Err(NotationError::UnknownCard { line: 4, text: "BOX7".to_string() }));
+        assert_eq!(with_move("P1 TRI7/CIR"), Err(NotationError::UnknownCard { line: 4, text: "CIR".to_string() }));
+        // Values the deck doesn't hold for the suit
+        for text in ["STA14", "STA8", "CIR99", "CIR15", "TRI0", "SQU-1"] {
+            assert_eq!(with_move(&format!("P1 {}", text)), Err(NotationError::UnknownCard { line: 4, text: text.to_string() }));
+        }
+        // A verb missing its count falls through to the card codes
+        assert_eq!(with_move("P1 DRAW"), Err(NotationError::UnknownCard { line: 4, text: "DRAW".to_string() }));
+        for line in ["P1", "X1 START", "P1 TRI7 +x", "P1 TRI7 !BOOM", "P1 WHOT>PURPLE", "P1 CHALLENGE X2 +1"] {
+            assert_eq!(with_move(line), Err(NotationError::BadMove { line: 4, text: line.to_string() }));
+        }
+    }
+}
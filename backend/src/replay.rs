@@ -0,0 +1,341 @@
+//! Move history for a match and a deterministic replayer.
+//!
+//! The PLAY_CHAIN appends one `MoveRecord` per accepted action. Starting from
+//! the `MatchHeader` (seed + seating) and applying the records in order through
+//! the same rules as the contract rebuilds every intermediate `MatchData`.
+
+use async_graphql::{ComplexObject, Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::game::{DrawOutcome, GameError};
+use crate::{
+    Card, CardSuit, ClockSettings, InactivityPolicy, MatchData, Player, RuleSet, SpecialEffect,
+    TimeControl,
+};
+
+/// Kind of action recorded in the move log
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum MoveKind {
+    Start,
+    Play,
+    Draw,
+    Timeout,
+    CallLastCard,
+    Challenge,
+    FalseChallenge,  // Failed challenge - the challenger drew the penalty
+    Forfeit,
+}
+
+/// One accepted action on the PLAY_CHAIN
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
+pub struct MoveRecord {
+    pub seat: usize,                    // Actor seat
+    pub kind: MoveKind,
+    pub card: Option<Card>,             // Card played
+    pub more_cards: Vec<Card>,          // Same-value cards played together with `card`
+    pub chosen_suit: Option<CardSuit>,  // Suit named with a Whot card
+    pub cards_drawn: u8,                // Cards drawn by the actor (the challenged player on a Challenge)
+    pub target_seat: Option<usize>,     // Challenged seat
+    #[graphql(skip)]
+    pub effect: Option<SpecialEffect>,
+    pub timestamp: u64,                 // Block time (micros)
+    pub pending_draw_stack: u8,         // Pending penalty after the action
+}
+
+#[ComplexObject]
+impl MoveRecord {
+    /// Name of the special effect that fired, if any
+    async fn special_effect(&self) -> Option<String> {
+        self.effect.as_ref().map(|effect| match effect {
+            SpecialEffect::PickTwo => "PickTwo".to_string(),
+            SpecialEffect::PickThree => "PickThree".to_string(),
+            SpecialEffect::HoldOn => "HoldOn".to_string(),
+            SpecialEffect::GeneralMarket => "GeneralMarket".to_string(),
+            SpecialEffect::WhotPlayed { chosen_suit } => format!("WhotPlayed({:?})", chosen_suit),
+            SpecialEffect::Suspension => "Suspension".to_string(),
+            SpecialEffect::Reverse => "Reverse".to_string(),
+        })
+    }
+}
+
+impl MoveRecord {
+    pub fn new(seat: usize, kind: MoveKind, timestamp: u64) -> Self {
+        Self {
+            seat,
+            kind,
+            card: None,
+            more_cards: Vec::new(),
+            chosen_suit: None,
+            cards_drawn: 0,
+            target_seat: None,
+            effect: None,
+            timestamp,
+            pending_draw_stack: 0,
+        }
+    }
+}
+
+/// Everything needed to rebuild a match besides its move log
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct MatchHeader {
+    pub match_id: u64,
+    pub seed: u64,
+    pub max_players: u8,
+    pub time_control: TimeControl,
+    pub clock: Option<ClockSettings>,
+    pub inactivity_policy: InactivityPolicy,
+    pub rules: RuleSet,
+    pub teams: Vec<u8>,                 // Team of each seat; empty outside team play
+    pub players: Vec<Option<Player>>,   // Seating at start (hands empty)
+}
+
+impl MatchHeader {
+    /// Header for a lobby that is about to start
+    pub fn from_lobby(match_data: &MatchData) -> Self {
+        Self {
+            match_id: match_data.match_id,
+            seed: match_data.seed,
+            max_players: match_data.max_players,
+            time_control: match_data.time_control,
+            clock: match_data.clock,
+            inactivity_policy: match_data.inactivity_policy,
+            rules: match_data.rules,
+            teams: match_data.teams.clone(),
+            players: match_data.public_view().players,
+        }
+    }
+
+    /// The lobby as it was right before the first move
+    pub fn initial_state(&self) -> MatchData {
+        let mut match_data = MatchData::new_lobby_with_time_control(self.max_players, self.seed, self.time_control);
+        match_data.match_id = self.match_id;
+        match_data.clock = self.clock;
+        match_data.inactivity_policy = self.inactivity_policy;
+        match_data.rules = self.rules;
+        match_data.teams = self.teams.clone();
+        match_data.players = self.players.iter()
+            .map(|p| p.as_ref().map(|player| {
+                let mut seated = Player::new(player.chain_id, player.owner, player.nickname.clone());
+                seated.bot = player.bot;
+                seated
+            }))
+            .collect();
+        match_data
+    }
+}
+
+/// Why a log could not be replayed
+#[derive(Clone, Debug, Error)]
+pub enum ReplayError {
+    #[error("step {step}: {source}")]
+    Rejected { step: usize, source: GameError },
+    #[error("step {step}: {card:?} is not in seat {seat}'s hand")]
+    CardNotInHand { step: usize, seat: usize, card: Card },
+    #[error("step {step}: replayed state no longer matches the log")]
+    Diverged { step: usize },
+}
+
+/// Rebuilds a match one record at a time
+pub struct Replayer {
+    state: MatchData,
+    step: usize,
+}
+
+impl Replayer {
+    pub fn new(header: &MatchHeader) -> Self {
+        Self {
+            state: header.initial_state(),
+            step: 0,
+        }
+    }
+
+    /// State after the records applied so far
+    pub fn state(&self) -> &MatchData {
+        &self.state
+    }
+
+    /// Apply the next record
+    pub fn apply(&mut self, record: &MoveRecord) -> Result<&MatchData, ReplayError> {
+        let step = self.step;
+        let rejected = |source| ReplayError::Rejected { step, source };
+        let state = &mut self.state;
+
+        let cards_drawn = match record.kind {
+            MoveKind::Start => {
+                state.start(record.timestamp).map_err(rejected)?;
+                0
+            }
+            MoveKind::Play => {
+                let card = record.card.clone().ok_or(ReplayError::Diverged { step })?;
+                let mut card_indices = Vec::with_capacity(1 + record.more_cards.len());
+                for card in std::iter::once(card).chain(record.more_cards.iter().cloned()) {
+                    let card_index = state.hand_index_of(record.seat, card.id)
+                        .ok_or(ReplayError::CardNotInHand { step, seat: record.seat, card })?;
+                    card_indices.push(card_index);
+                }
+                state.play_cards(record.seat, &card_indices, record.chosen_suit, record.timestamp)
+                    .map_err(rejected)?
+                    .penalty_drawn
+            }
+            MoveKind::Draw => match state.draw_card(record.seat, record.timestamp).map_err(rejected)? {
+                DrawOutcome::Drawn { count, .. } => count,
+                DrawOutcome::DeckExhausted { .. } => 0,
+            },
+            MoveKind::Timeout => {
+                let outcome = state.timeout_turn(record.timestamp).map_err(rejected)?;
+                if outcome.seat != record.seat {
+                    return Err(ReplayError::Diverged { step });
+                }
+                outcome.cards_drawn
+            }
+            MoveKind::CallLastCard => state.call_last_card(record.seat).map_err(rejected)?.penalty_cards,
+            MoveKind::Challenge | MoveKind::FalseChallenge => {
+                let target = record.target_seat.ok_or(ReplayError::Diverged { step })?;
+                let outcome = state.challenge_last_card(record.seat, target).map_err(rejected)?;
+                if outcome.success != (record.kind == MoveKind::Challenge) {
+                    return Err(ReplayError::Diverged { step });
+                }
+                outcome.penalty_cards
+            }
+            MoveKind::Forfeit => {
+                state.forfeit(record.seat, record.timestamp).map_err(rejected)?;
+                0
+            }
+        };
+
+        if cards_drawn != record.cards_drawn || state.pending_total() != record.pending_draw_stack {
+            return Err(ReplayError::Diverged { step });
+        }
+
+        self.step += 1;
+        Ok(&self.state)
+    }
+}
+
+/// Every intermediate state of a match: the lobby first, then one per record
+pub fn replay(header: &MatchHeader, log: &[MoveRecord]) -> Result<Vec<MatchData>, ReplayError> {
+    let mut replayer = Replayer::new(header);
+    let mut states = Vec::with_capacity(log.len() + 1);
+    states.push(replayer.state().clone());
+    for record in log {
+        states.push(replayer.apply(record)?.clone());
+    }
+    Ok(states)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{self, BotAction, BotDifficulty};
+    use crate::game::{seated_match, standard_deck, WHOT_VALUE};
+    use crate::MatchStatus;
+
+    /// Three-seat lobby with `seed`, ready to start
+    fn lobby(seed: u64) -> MatchData {
+        let mut match_data = seated_match(&[true; 3], seed);
+        match_data.match_id = 5;
+        match_data
+    }
+
+    /// Play a match out with Greedy bots in every seat, logging each action like the PLAY_CHAIN
+    fn play_out(seed: u64) -> (MatchHeader, Vec<MoveRecord>, MatchData) {
+        let mut live = lobby(seed);
+        let header = MatchHeader::from_lobby(&live);
+        let mut log = Vec::new();
+        live.start(0).expect("match starts");
+        log.push(MoveRecord::new(0, MoveKind::Start, 0));
+
+        for step in 1..500u64 {
+            if live.status != MatchStatus::InProgress {
+                break;
+            }
+            let now = step * 1_000;
+            let seat = live.current_player_index;
+            let mut record = match bot::choose_action(&live, seat, BotDifficulty::Greedy, step) {
+                BotAction::Play { card_id, chosen_suit } => {
+                    let card_index = live.hand_index_of(seat, card_id).expect("bot picks from its hand");
+                    let outcome = live.play_cards(seat, &[card_index], chosen_suit, now).expect("bot plays a legal card");
+                    let mut record = MoveRecord::new(seat, MoveKind::Play, now);
+                    record.chosen_suit = chosen_suit.filter(|_| outcome.card.value == WHOT_VALUE);
+                    record.card = Some(outcome.card);
+                    record.cards_drawn = outcome.penalty_drawn;
+                    record.effect = outcome.special_effect;
+                    record
+                }
+                BotAction::Draw => {
+                    let mut record = MoveRecord::new(seat, MoveKind::Draw, now);
+                    if let DrawOutcome::Drawn { count, .. } = live.draw_card(seat, now).expect("bot draws when stuck") {
+                        record.cards_drawn = count;
+                    }
+                    record
+                }
+            };
+            record.pending_draw_stack = live.pending_total();
+            log.push(record);
+
+            // Down to the last card: call it before anyone can challenge
+            let holds_one = live.players[seat].as_ref().is_some_and(|player| player.hand.len() == 1);
+            if live.status == MatchStatus::InProgress && holds_one {
+                live.call_last_card(seat).expect("call is in the window");
+                let mut record = MoveRecord::new(seat, MoveKind::CallLastCard, now);
+                record.pending_draw_stack = live.pending_total();
+                log.push(record);
+            }
+        }
+        (header, log, live)
+    }
+
+    /// What a replay has to reproduce: hands, piles, turn and result
+    type Position = (Vec<Option<Vec<Card>>>, Vec<Card>, Vec<Card>, usize, u64, MatchStatus, Option<usize>);
+
+    fn position(match_data: &MatchData) -> Position {
+        (
+            match_data.players.iter().map(|p| p.as_ref().map(|player| player.hand.clone())).collect(),
+            match_data.deck.clone(),
+            match_data.discard_pile.clone(),
+            match_data.current_player_index,
+            match_data.turn_number,
+            match_data.status,
+            match_data.winner_index,
+        )
+    }
+
+    #[test]
+    fn replaying_the_log_rebuilds_the_live_match() {
+        for seed in [1, 42, 1_712_345_678_901_234] {
+            let (header, log, live) = play_out(seed);
+            assert_eq!(live.status, MatchStatus::Finished, "seed {}", seed);
+            assert!(log.iter().any(|record| record.kind == MoveKind::Draw), "seed {} never drew", seed);
+
+            let states = replay(&header, &log).expect("untouched log replays");
+            assert_eq!(states.len(), log.len() + 1);
+            assert_eq!(states[0].status, MatchStatus::Waiting);
+            assert_eq!(position(states.last().expect("final state")), position(&live), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn a_tampered_log_is_caught() {
+        let (header, log, _) = play_out(42);
+
+        // Claim an extra card on the first draw
+        let mut tampered = log.clone();
+        let step = tampered.iter().position(|record| record.kind == MoveKind::Draw).expect("a draw");
+        tampered[step].cards_drawn += 1;
+        assert!(matches!(replay(&header, &tampered), Err(ReplayError::Diverged { step: at }) if at == step));
+
+        // Play a card the seat never held
+        let mut tampered = log;
+        let step = tampered.iter().position(|record| record.kind == MoveKind::Play).expect("a play");
+        let seat = tampered[step].seat;
+        let states = replay(&header, &tampered[..step]).expect("log up to the play replays");
+        let hand = &states.last().expect("state before the play").players[seat].as_ref().expect("seated").hand;
+        let foreign = standard_deck().into_iter()
+            .find(|card| !hand.contains(card))
+            .expect("a card outside the hand");
+        tampered[step].card = Some(foreign);
+        assert!(matches!(replay(&header, &tampered), Err(ReplayError::CardNotInHand { step: at, .. }) if at == step));
+    }
+}
@@ -9,6 +9,11 @@ use linera_sdk::{
     views::View,
     Service, ServiceRuntime,
 };
+use linot::chat::{ChatEntry, Reaction, CHAT_HISTORY_LIMIT, REACTION_TTL_MICROS};
+use linot::game::LegalMoves;
+use linot::notation::{self, GameRecord, RecordFormat};
+use linot::replay::MoveRecord;
+use linot::tournament::{Round, Standing, Tournament};
 use linot::{Card, LinotAbi, MatchData, MatchStatus, Operation};
 use self::state::LinotState;
 use linot::UserStatus;
@@ -45,6 +50,7 @@ impl Service for LinotService {
         Schema::build(
             QueryRoot {
                 state: self.state.clone(),
+                now: self.runtime.system_time().micros(),
             },
             Operation::mutation_root(self.runtime.clone()),
             EmptySubscription,
@@ -59,6 +65,7 @@ impl Service for LinotService {
 #[derive(Clone)]
 struct QueryRoot {
     state: Arc<LinotState>,
+    now: u64,  // Query time, for expiring reactions
 }
 
 #[Object]
@@ -89,6 +96,13 @@ impl QueryRoot {
         Vec::new()
     }
 
+    /// What `owner` may do right now: playable hand indices, draw rules, pending penalty
+    async fn legal_moves(&self, owner: AccountOwner) -> Option<LegalMoves> {
+        let match_data = self.state.match_data.get();
+        let seat = match_data.seat_of(&owner)?;
+        Some(match_data.legal_moves(seat))
+    }
+
     /// Get basic match info (player count, max players, status)
     async fn match_info(&self) -> Option<MatchInfo> {
         // Query PLAY_CHAIN authoritative state
@@ -103,6 +117,7 @@ impl QueryRoot {
             status: match_data.status,
             deck_size: match_data.deck_size,
             top_card: match_data.discard_pile.last().cloned(),
+            spectator_count: match_data.spectator_count,
         })
     }
 
@@ -120,6 +135,133 @@ impl QueryRoot {
     async fn user_status(&self) -> UserStatus {
         *self.state.user_status.get()
     }
+
+    /// Get the play chain this user is spectating
+    async fn spectated_table(&self) -> Option<ChainId> {
+        *self.state.spectated_play_chain.get()
+    }
+
+    /// Get the spectated match (public state only, never contains hands)
+    async fn spectated_match(&self) -> Option<MatchData> {
+        self.state.spectated_play_chain.get().as_ref()?;
+        self.state.local_match.get().as_ref().map(MatchData::public_view)
+    }
+
+    /// Get the number of spectators watching this PLAY_CHAIN's match
+    async fn spectator_count(&self) -> usize {
+        self.state.match_data.get().spectator_count
+    }
+
+    /// Get every recorded move of a finished match hosted on this PLAY_CHAIN
+    async fn move_history(&self, match_id: u64) -> async_graphql::Result<Vec<MoveRecord>> {
+        self.ensure_finished(match_id)?;
+        self.load_move_log(match_id).await
+    }
+
+    /// Rebuild a finished match move by move from its seed and move log
+    async fn replay(&self, match_id: u64) -> async_graphql::Result<Vec<ReplayStep>> {
+        self.ensure_finished(match_id)?;
+        let header = self.state.match_headers.get(&match_id).await?
+            .ok_or("Unknown match or match not started")?;
+        let log = self.load_move_log(match_id).await?;
+        let states = linot::replay::replay(&header, &log)?;
+
+        Ok(states.into_iter()
+            .enumerate()
+            .map(|(step, state)| ReplayStep {
+                step,
+                record: step.checked_sub(1).map(|index| log[index].clone()),
+                state: state.public_view(),
+            })
+            .collect())
+    }
+
+    /// Export a finished match in the portable game notation (see docs/GAME_NOTATION.md)
+    async fn game_record(&self, match_id: u64, format: RecordFormat) -> async_graphql::Result<String> {
+        self.ensure_finished(match_id)?;
+        let header = self.state.match_headers.get(&match_id).await?
+            .ok_or("Unknown match or match not started")?;
+        let log = self.load_move_log(match_id).await?;
+        let record = GameRecord::from_log(&header, notation::rules_tag(&header.rules), &log);
+        Ok(record.to_notation(format))
+    }
+
+    /// Get the latest chat messages of a match, oldest first (defaults to the current match).
+    /// Only the last CHAT_LOG_CAPACITY messages of the table are kept.
+    async fn chat_history(&self, match_id: Option<u64>, limit: Option<usize>) -> async_graphql::Result<Vec<ChatEntry>> {
+        let match_id = match_id.unwrap_or(self.state.match_data.get().match_id);
+        let limit = limit.unwrap_or(CHAT_HISTORY_LIMIT).min(CHAT_HISTORY_LIMIT);
+        let mut entries: Vec<ChatEntry> = self.state.chat_log.elements().await?
+            .into_iter()
+            .filter(|entry| entry.match_id == match_id)
+            .collect();
+        entries.drain(..entries.len().saturating_sub(limit));
+        Ok(entries)
+    }
+
+    /// Get the chains muted in the current match's chat
+    async fn muted_chats(&self) -> Vec<ChainId> {
+        let match_id = self.state.match_data.get().match_id;
+        self.state.muted_chats.get().muted_in(match_id)
+    }
+
+    /// Get the reactions seen in the last few seconds (USER_CHAIN)
+    async fn recent_reactions(&self) -> Vec<Reaction> {
+        self.state.recent_reactions.get().iter()
+            .filter(|reaction| self.now.saturating_sub(reaction.timestamp) < REACTION_TTL_MICROS)
+            .cloned()
+            .collect()
+    }
+
+    /// Get the tournament run from this chain (TOURNAMENT_CHAIN)
+    async fn tournament(&self) -> Option<Tournament> {
+        self.state.tournament.get().clone()
+    }
+
+    /// Get every round seated so far, with its tables and results
+    async fn bracket(&self) -> Vec<Round> {
+        self.state.tournament.get().as_ref()
+            .map(|tournament| tournament.rounds.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the entrants ranked: round reached in single elimination, points in Swiss
+    async fn standings(&self) -> Vec<Standing> {
+        self.state.tournament.get().as_ref()
+            .map(Tournament::standings)
+            .unwrap_or_default()
+    }
+
+    /// Get the tournament this user signed up for
+    async fn my_tournament(&self) -> Option<ChainId> {
+        *self.state.tournament_chain.get()
+    }
+}
+
+impl QueryRoot {
+    /// The seed rebuilds every hand, so nothing derived from it leaves the chain mid-match
+    fn ensure_finished(&self, match_id: u64) -> async_graphql::Result<()> {
+        let current = self.state.match_data.get();
+        if current.match_id == match_id && current.status != MatchStatus::Finished {
+            return Err("Match is still being played".into());
+        }
+        Ok(())
+    }
+
+    async fn load_move_log(&self, match_id: u64) -> async_graphql::Result<Vec<MoveRecord>> {
+        match self.state.move_logs.try_load_entry(&match_id).await? {
+            Some(log) => Ok(log.read(0..log.count()).await?),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// One step of a replay: the move applied (none for the lobby) and the state after it
+#[derive(SimpleObject)]
+struct ReplayStep {
+    step: usize,
+    record: Option<MoveRecord>,
+    state: MatchData,
 }
 
 /// Match info for queries
@@ -130,4 +272,5 @@ struct MatchInfo {
     status: MatchStatus,
     deck_size: usize,
     top_card: Option<Card>,
+    spectator_count: usize,
 }
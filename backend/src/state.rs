@@ -1,9 +1,12 @@
 use async_graphql::SimpleObject;
 use linera_sdk::{
     linera_base_types::ChainId,
-    views::{linera_views, RegisterView, RootView, ViewStorageContext},
+    views::{linera_views, CollectionView, LogView, MapView, QueueView, RegisterView, RootView, ViewStorageContext},
 };
 
+use linot::chat::{ChatEntry, MuteList, RateWindow, Reaction};
+use linot::replay::{MatchHeader, MoveRecord};
+use linot::tournament::Tournament;
 use linot::{MatchData, UserStatus};
 
 /// The application state
@@ -14,6 +17,33 @@ pub struct LinotState {
     /// The authoritative match state (on PLAY_CHAIN only)
     pub match_data: RegisterView<MatchData>,
     
+    /// Append-only move history, one log per match_id (on PLAY_CHAIN only)
+    #[graphql(skip)]
+    pub move_logs: CollectionView<u64, LogView<MoveRecord>>,
+    
+    /// Seed and seating of every started match, for replays (on PLAY_CHAIN only)
+    #[graphql(skip)]
+    pub match_headers: MapView<u64, MatchHeader>,
+    
+    /// Latest table chat of every match, capped at CHAT_LOG_CAPACITY (on PLAY_CHAIN only)
+    #[graphql(skip)]
+    pub chat_log: QueueView<ChatEntry>,
+    
+    /// Recent chat send times per sender chain (on PLAY_CHAIN only)
+    #[graphql(skip)]
+    pub chat_windows: MapView<ChainId, RateWindow>,
+    
+    /// Coordinator that opened this chain for a tournament table (on PLAY_CHAIN only)
+    pub table_coordinator: RegisterView<Option<ChainId>>,
+    
+    /// Chains the host muted in the current match's chat (on PLAY_CHAIN only)
+    #[graphql(skip)]
+    pub muted_chats: RegisterView<MuteList>,
+    
+    /// Time of each sender chain's last reaction, for the cool-down (on PLAY_CHAIN only)
+    #[graphql(skip)]
+    pub last_reactions: MapView<ChainId, u64>,
+    
     // ==== USER CHAIN STATE ====
     /// Copy of match state for local queries (on USER_CHAIN)
     pub local_match: RegisterView<Option<MatchData>>,
@@ -21,9 +51,22 @@ pub struct LinotState {
     /// Which play chain this user is subscribed to
     pub subscribed_play_chain: RegisterView<Option<ChainId>>,
     
+    /// Which play chain this user is watching as a spectator (no seat)
+    pub spectated_play_chain: RegisterView<Option<ChainId>>,
+    
     /// Player's own nickname
     pub player_nickname: RegisterView<Option<String>>,
     
+    /// Reactions seen in the last few seconds (on USER_CHAIN)
+    pub recent_reactions: RegisterView<Vec<Reaction>>,
+    
     /// User status tracking (imported from lib.rs)
     pub user_status: RegisterView<UserStatus>,
+    
+    /// Tournament this user signed up for
+    pub tournament_chain: RegisterView<Option<ChainId>>,
+    
+    // ==== TOURNAMENT CHAIN STATE ====
+    /// Bracket, entrants and results (on TOURNAMENT_CHAIN only)
+    pub tournament: RegisterView<Option<Tournament>>,
 }
@@ -0,0 +1,488 @@
+//! Tournament brackets run from a coordinator chain.
+//!
+//! Every round splits the field into tables of up to `table_size` players. Each
+//! table is played out as an ordinary match on its own PLAY_CHAIN, which reports
+//! back through its `MatchEnded` event. Single elimination keeps the table
+//! winners until one is left; Swiss plays a fixed number of rounds, seating
+//! players on equal points together, and ranks the field on points. A table
+//! left with a single player is a bye and counts as a win.
+
+use std::cmp::Reverse;
+
+use async_graphql::{Enum, InputObject, SimpleObject};
+use linera_sdk::linera_base_types::{AccountOwner, ChainId};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{RuleSet, TimeControl, MAX_PLAYERS, MIN_PLAYERS};
+
+/// Most players one tournament takes
+pub const MAX_ENTRANTS: usize = 64;
+
+/// Most rounds a Swiss tournament can be set to
+pub const MAX_SWISS_ROUNDS: u8 = 10;
+
+/// Swiss points for winning a table (byes included)
+pub const WIN_POINTS: u32 = 3;
+
+/// Swiss points for every player at a drawn table
+pub const DRAW_POINTS: u32 = 1;
+
+/// Why a tournament action was rejected
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum TournamentError {
+    #[error("Invalid tournament settings: {0}")]
+    InvalidSettings(String),
+    #[error("Only the organizer can do that")]
+    NotOrganizer,
+    #[error("Registration is closed")]
+    RegistrationClosed,
+    #[error("Already registered")]
+    AlreadyRegistered,
+    #[error("Tournament is full")]
+    Full,
+    #[error("Not enough entrants. Need at least {needed}. Current: {current}")]
+    NotEnoughEntrants { needed: usize, current: usize },
+    #[error("Tournament is not running")]
+    NotRunning,
+    #[error("No open table is played on that chain")]
+    UnknownTable,
+}
+
+/// How players advance from round to round
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum TournamentFormat {
+    SingleElimination,  // Table winners go through until one is left
+    Swiss,              // Everyone plays every round, ranked on points
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Enum, PartialEq, Eq)]
+pub enum TournamentStatus {
+    Registering,
+    Running,
+    Finished,
+}
+
+/// Picked by the organizer when the tournament is created
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SimpleObject, InputObject, PartialEq, Eq)]
+#[graphql(input_name = "TournamentSettingsInput")]
+pub struct TournamentSettings {
+    pub format: TournamentFormat,
+    pub swiss_rounds: u8,            // Rounds played in Swiss, ignored otherwise
+    pub table_size: u8,              // Most players seated at one table
+    pub time_control: TimeControl,   // Turn timer of every table
+    pub rules: RuleSet,              // House rules of every table
+}
+
+impl TournamentSettings {
+    pub fn validate(&self) -> Result<(), TournamentError> {
+        if self.table_size < MIN_PLAYERS || self.table_size > MAX_PLAYERS {
+            return Err(TournamentError::InvalidSettings(format!(
+                "table size must be between {} and {}", MIN_PLAYERS, MAX_PLAYERS
+            )));
+        }
+        if self.format == TournamentFormat::Swiss && (self.swiss_rounds == 0 || self.swiss_rounds > MAX_SWISS_ROUNDS) {
+            return Err(TournamentError::InvalidSettings(format!(
+                "Swiss rounds must be between 1 and {}", MAX_SWISS_ROUNDS
+            )));
+        }
+        self.rules.validate().map_err(|error| TournamentError::InvalidSettings(error.to_string()))
+    }
+}
+
+/// A registered player; their seed is the entrant index (registration order)
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Entrant {
+    pub owner: AccountOwner,
+    pub chain_id: ChainId,          // USER_CHAIN told about table assignments
+    pub nickname: String,
+    pub points: u32,
+    pub wins: u32,
+    pub tables_played: u32,
+    pub eliminated: bool,           // Single elimination only
+}
+
+/// One match of a round
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Table {
+    pub number: usize,
+    pub entrants: Vec<usize>,            // Entrant indices in seat order
+    pub play_chain_id: Option<ChainId>,  // Chain the match is played on (none for a bye)
+    pub winner: Option<usize>,           // Entrant index; none for a Swiss draw
+    pub finished: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Round {
+    pub number: u8,
+    pub tables: Vec<Table>,
+}
+
+/// Place of one entrant in the standings
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Standing {
+    pub rank: usize,
+    pub entrant: usize,
+    pub nickname: String,
+    pub points: u32,
+    pub wins: u32,
+    pub tables_played: u32,
+    pub eliminated: bool,
+}
+
+/// What a table result led to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoundProgress {
+    /// Other tables of the round are still playing
+    Waiting,
+    /// The round is over and the next one is seated
+    NextRound(u8),
+    /// The tournament is over
+    Finished { champion: Option<usize> },
+}
+
+/// Tournament state kept on the coordinator chain
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject, PartialEq, Eq)]
+pub struct Tournament {
+    pub name: String,
+    pub organizer: AccountOwner,
+    pub settings: TournamentSettings,
+    pub status: TournamentStatus,
+    pub entrants: Vec<Entrant>,
+    pub rounds: Vec<Round>,
+    pub champion: Option<usize>,    // Entrant index once finished
+}
+
+impl Tournament {
+    pub fn new(name: String, organizer: AccountOwner, settings: TournamentSettings) -> Result<Self, TournamentError> {
+        settings.validate()?;
+        Ok(Self {
+            name,
+            organizer,
+            settings,
+            status: TournamentStatus::Registering,
+            entrants: Vec::new(),
+            rounds: Vec::new(),
+            champion: None,
+        })
+    }
+
+    /// Add a player while registration is open, returning their entrant index
+    pub fn register(&mut self, owner: AccountOwner, chain_id: ChainId, nickname: String) -> Result<usize, TournamentError> {
+        if self.status != TournamentStatus::Registering {
+            return Err(TournamentError::RegistrationClosed);
+        }
+        if self.entrants.iter().any(|entrant| entrant.owner == owner) {
+            return Err(TournamentError::AlreadyRegistered);
+        }
+        if self.entrants.len() >= MAX_ENTRANTS {
+            return Err(TournamentError::Full);
+        }
+        self.entrants.push(Entrant {
+            owner,
+            chain_id,
+            nickname,
+            points: 0,
+            wins: 0,
+            tables_played: 0,
+            eliminated: false,
+        });
+        Ok(self.entrants.len() - 1)
+    }
+
+    /// Close registration and seat the first round (organizer only)
+    pub fn start(&mut self, by: &AccountOwner) -> Result<u8, TournamentError> {
+        if *by != self.organizer {
+            return Err(TournamentError::NotOrganizer);
+        }
+        if self.status != TournamentStatus::Registering {
+            return Err(TournamentError::RegistrationClosed);
+        }
+        let needed = MIN_PLAYERS as usize;
+        if self.entrants.len() < needed {
+            return Err(TournamentError::NotEnoughEntrants { needed, current: self.entrants.len() });
+        }
+        self.status = TournamentStatus::Running;
+        Ok(self.seat_round())
+    }
+
+    /// Round being played
+    pub fn current_round(&self) -> Option<&Round> {
+        self.rounds.last()
+    }
+
+    /// Tables of the current round still waiting for a play chain
+    pub fn tables_to_open(&self) -> Vec<usize> {
+        self.current_round()
+            .map(|round| round.tables.iter()
+                .filter(|table| !table.finished && table.play_chain_id.is_none())
+                .map(|table| table.number)
+                .collect())
+            .unwrap_or_default()
+    }
+
+    /// Record the chain `table` of the current round is played on
+    pub fn assign_table(&mut self, table: usize, play_chain_id: ChainId) {
+        if let Some(table) = self.rounds.last_mut().and_then(|round| round.tables.get_mut(table)) {
+            table.play_chain_id = Some(play_chain_id);
+        }
+    }
+
+    /// Settle the table played on `play_chain_id`; `winner_seat` is `None` on a draw.
+    /// A drawn single-elimination table sends its best seed through.
+    pub fn record_result(&mut self, play_chain_id: ChainId, winner_seat: Option<usize>) -> Result<RoundProgress, TournamentError> {
+        if self.status != TournamentStatus::Running {
+            return Err(TournamentError::NotRunning);
+        }
+        let single_elimination = self.settings.format == TournamentFormat::SingleElimination;
+        let table = self.rounds.last_mut()
+            .and_then(|round| round.tables.iter_mut()
+                .find(|table| !table.finished && table.play_chain_id == Some(play_chain_id)))
+            .ok_or(TournamentError::UnknownTable)?;
+
+        let mut winner = winner_seat.and_then(|seat| table.entrants.get(seat).copied());
+        if single_elimination && winner.is_none() {
+            winner = table.entrants.iter().copied().min();
+        }
+        table.winner = winner;
+        table.finished = true;
+        let seated = table.entrants.clone();
+        self.score_table(&seated, winner);
+
+        let round_over = self.current_round().is_some_and(|round| round.tables.iter().all(|table| table.finished));
+        if !round_over {
+            return Ok(RoundProgress::Waiting);
+        }
+        Ok(self.advance())
+    }
+
+    /// Entrants ranked: by round reached in single elimination, by points in Swiss
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut order: Vec<usize> = (0..self.entrants.len()).collect();
+        match self.settings.format {
+            TournamentFormat::SingleElimination => order.sort_by_key(|&index| {
+                let entrant = &self.entrants[index];
+                (entrant.eliminated, Reverse(entrant.wins), index)
+            }),
+            TournamentFormat::Swiss => order.sort_by_key(|&index| self.swiss_key(index)),
+        }
+        order.into_iter()
+            .enumerate()
+            .map(|(position, index)| {
+                let entrant = &self.entrants[index];
+                Standing {
+                    rank: position + 1,
+                    entrant: index,
+                    nickname: entrant.nickname.clone(),
+                    points: entrant.points,
+                    wins: entrant.wins,
+                    tables_played: entrant.tables_played,
+                    eliminated: entrant.eliminated,
+                }
+            })
+            .collect()
+    }
+
+    // ================================================================================
+    // INTERNAL HELPERS
+    // ================================================================================
+
+    fn swiss_key(&self, index: usize) -> (Reverse<u32>, Reverse<u32>, usize) {
+        let entrant = &self.entrants[index];
+        (Reverse(entrant.points), Reverse(entrant.wins), index)
+    }
+
+    fn score_table(&mut self, seated: &[usize], winner: Option<usize>) {
+        let single_elimination = self.settings.format == TournamentFormat::SingleElimination;
+        for &index in seated {
+            let entrant = &mut self.entrants[index];
+            entrant.tables_played += 1;
+            match winner {
+                Some(winner) if winner == index => {
+                    entrant.wins += 1;
+                    entrant.points += WIN_POINTS;
+                }
+                Some(_) => entrant.eliminated = single_elimination,
+                None => entrant.points += DRAW_POINTS,
+            }
+        }
+    }
+
+    /// Seat the next round or finish after a completed round
+    fn advance(&mut self) -> RoundProgress {
+        let over = match self.settings.format {
+            TournamentFormat::SingleElimination => self.entrants.iter().filter(|entrant| !entrant.eliminated).count() <= 1,
+            TournamentFormat::Swiss => self.rounds.len() >= self.settings.swiss_rounds as usize,
+        };
+        if over {
+            self.status = TournamentStatus::Finished;
+            self.champion = self.standings().first().map(|standing| standing.entrant);
+            return RoundProgress::Finished { champion: self.champion };
+        }
+        RoundProgress::NextRound(self.seat_round())
+    }
+
+    /// Split the field into balanced tables for a new round and settle its byes
+    fn seat_round(&mut self) -> u8 {
+        let mut field: Vec<usize> = (0..self.entrants.len())
+            .filter(|&index| !self.entrants[index].eliminated)
+            .collect();
+        let table_size = self.settings.table_size as usize;
+        let table_count = field.len().div_ceil(table_size).max(1);
+        let mut seating: Vec<Vec<usize>> = vec![Vec::new(); table_count];
+
+        match self.settings.format {
+            // Snake the seeds across the tables so the favourites meet as late as possible
+            TournamentFormat::SingleElimination => {
+                for (position, index) in field.into_iter().enumerate() {
+                    let lap = position / table_count;
+                    let column = position % table_count;
+                    let table = if lap.is_multiple_of(2) { column } else { table_count - 1 - column };
+                    seating[table].push(index);
+                }
+            }
+            // Players on the same points sit together, the lowest-ranked gets any bye
+            TournamentFormat::Swiss => {
+                field.sort_by_key(|&index| self.swiss_key(index));
+                let base = field.len() / table_count;
+                let larger = field.len() % table_count;
+                let mut players = field.into_iter();
+                for (table, seats) in seating.iter_mut().enumerate() {
+                    let size = if table < larger { base + 1 } else { base };
+                    seats.extend(players.by_ref().take(size));
+                }
+            }
+        }
+
+        let number = self.rounds.len() as u8 + 1;
+        let tables = seating.into_iter()
+            .enumerate()
+            .map(|(table, entrants)| Table {
+                number: table,
+                entrants,
+                play_chain_id: None,
+                winner: None,
+                finished: false,
+            })
+            .collect();
+        self.rounds.push(Round { number, tables });
+
+        let byes: Vec<usize> = self.rounds.last()
+            .map(|round| round.tables.iter()
+                .filter(|table| table.entrants.len() == 1)
+                .map(|table| table.entrants[0])
+                .collect())
+            .unwrap_or_default();
+        for entrant in byes {
+            if let Some(table) = self.rounds.last_mut()
+                .and_then(|round| round.tables.iter_mut().find(|table| table.entrants == [entrant]))
+            {
+                table.winner = Some(entrant);
+                table.finished = true;
+            }
+            self.score_table(&[entrant], Some(entrant));
+        }
+        number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::CryptoHash;
+
+    use super::*;
+
+    fn tournament(format: TournamentFormat, table_size: u8, entrants: u8) -> Tournament {
+        let settings = TournamentSettings {
+            format,
+            swiss_rounds: 2,
+            table_size,
+            time_control: TimeControl::default(),
+            rules: RuleSet::default(),
+        };
+        let mut tournament = Tournament::new("Friday".to_string(), AccountOwner::Reserved(0), settings)
+            .expect("valid settings");
+        for index in 0..entrants {
+            let chain_id = ChainId(CryptoHash::from([index; 32]));
+            tournament.register(AccountOwner::Reserved(index + 1), chain_id, format!("E{}", index + 1))
+                .expect("registration open");
+        }
+        tournament
+    }
+
+    /// Give every open table of the current round a chain of its own
+    fn open_tables(tournament: &mut Tournament) -> Vec<ChainId> {
+        let round = tournament.rounds.len() as u8;
+        tournament.tables_to_open()
+            .into_iter()
+            .map(|table| {
+                let chain_id = ChainId(CryptoHash::from([100 + round * 10 + table as u8; 32]));
+                tournament.assign_table(table, chain_id);
+                chain_id
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_elimination_runs_to_a_champion() {
+        let mut tournament = tournament(TournamentFormat::SingleElimination, 2, 5);
+        assert_eq!(tournament.start(&AccountOwner::Reserved(3)), Err(TournamentError::NotOrganizer));
+        assert_eq!(tournament.start(&AccountOwner::Reserved(0)), Ok(1));
+        assert_eq!(
+            tournament.register(AccountOwner::Reserved(9), ChainId(CryptoHash::from([9; 32])), "Late".to_string()),
+            Err(TournamentError::RegistrationClosed),
+        );
+
+        // Five players at three tables: the seeds snake back, so the top seed has the bye
+        let seating: Vec<Vec<usize>> = tournament.rounds[0].tables.iter().map(|table| table.entrants.clone()).collect();
+        assert_eq!(seating, vec![vec![0], vec![1, 4], vec![2, 3]]);
+        assert_eq!(tournament.entrants[0].wins, 1);
+        let chains = open_tables(&mut tournament);
+        assert_eq!(chains.len(), 2);
+
+        assert_eq!(tournament.record_result(chains[0], Some(1)), Ok(RoundProgress::Waiting));
+        assert_eq!(tournament.record_result(chains[0], Some(0)), Err(TournamentError::UnknownTable));
+        // A draw sends the better seed through
+        assert_eq!(tournament.record_result(chains[1], None), Ok(RoundProgress::NextRound(2)));
+
+        // Entrants 0, 2 and 4 are left
+        assert_eq!(tournament.standings().iter().filter(|standing| !standing.eliminated).count(), 3);
+        let chains = open_tables(&mut tournament);
+        let mut progress = RoundProgress::Waiting;
+        for chain_id in chains {
+            progress = tournament.record_result(chain_id, Some(0)).expect("table is open");
+        }
+        while let RoundProgress::NextRound(_) = progress {
+            let chains = open_tables(&mut tournament);
+            for chain_id in chains {
+                progress = tournament.record_result(chain_id, Some(0)).expect("table is open");
+            }
+        }
+        let RoundProgress::Finished { champion: Some(champion) } = progress else {
+            panic!("tournament should be over");
+        };
+        assert_eq!(tournament.status, TournamentStatus::Finished);
+        assert_eq!(tournament.standings()[0].entrant, champion);
+        assert_eq!(tournament.entrants.iter().filter(|entrant| !entrant.eliminated).count(), 1);
+    }
+
+    #[test]
+    fn swiss_seats_equal_points_together() {
+        let mut tournament = tournament(TournamentFormat::Swiss, 2, 4);
+        tournament.start(&AccountOwner::Reserved(0)).expect("enough entrants");
+        let chains = open_tables(&mut tournament);
+        tournament.record_result(chains[0], Some(1)).expect("table is open");
+        assert_eq!(tournament.record_result(chains[1], None), Ok(RoundProgress::NextRound(2)));
+
+        // The winner meets the best of the drawn pair, the loser the other one
+        let seating: Vec<Vec<usize>> = tournament.rounds[1].tables.iter().map(|table| table.entrants.clone()).collect();
+        assert_eq!(seating, vec![vec![1, 2], vec![3, 0]]);
+
+        let chains = open_tables(&mut tournament);
+        tournament.record_result(chains[0], Some(0)).expect("table is open");
+        let progress = tournament.record_result(chains[1], Some(0)).expect("table is open");
+        assert_eq!(progress, RoundProgress::Finished { champion: Some(1) });
+        let points: Vec<u32> = tournament.standings().iter().map(|standing| standing.points).collect();
+        assert_eq!(points, vec![6, 4, 1, 0]);
+    }
+}
@@ -32,10 +32,12 @@ export function useWhotGame(playerNumber: 1 | 2) {
           matchState {
             status
             currentPlayerIndex
-            discardPile { suit value }
+            turnNumber
+            lastCardSeq
+            discardPile { id suit value }
             deckSize
             pendingDrawStack
-            activeDemandSuit
+            whotDemand { suit playerNickname }
             winnerIndex
             players {
               owner
@@ -45,7 +47,7 @@ export function useWhotGame(playerNumber: 1 | 2) {
             }
             maxPlayers
           }
-          myHand(owner: $owner) { suit value }
+          myHand(owner: $owner) { id suit value }
         }
       `,
         { owner: currentOwner }
@@ -92,8 +94,10 @@ export function useWhotGame(playerNumber: 1 | 2) {
         deckSize: data.matchState.deckSize || 0,
         currentPlayerIndex: data.matchState.currentPlayerIndex || 0,
         status: data.matchState.status || "WAITING",
-        activeShapeDemand: data.matchState.activeDemandSuit || null,
+        activeShapeDemand: data.matchState.whotDemand?.suit || null,
         pendingPenalty: data.matchState.pendingDrawStack || 0,
+        turnNumber: data.matchState.turnNumber || 0,
+        lastCardSeq: data.matchState.lastCardSeq || 0,
         winnerIndex:
           data.matchState.winnerIndex !== undefined
             ? data.matchState.winnerIndex
@@ -137,14 +141,25 @@ export function useWhotGame(playerNumber: 1 | 2) {
   // Mutations (use mutateUserChain)
   const playCard = useCallback(
     async (cardIndex: number, chosenSuit?: string) => {
+      // Send the card's id (not its index) so a hand reshuffled by a
+      // penalty draw can't make the chain play a different card
+      const card = gameState?.myCards[cardIndex];
+      if (!card) {
+        throw new Error(`No card at index ${cardIndex}`);
+      }
       try {
         await mutateUserChain(
           `
-        mutation PlayCard($cardIndex: Int!, $chosenSuit: String) {
-          playCard(cardIndex: $cardIndex, chosenSuit: $chosenSuit)
+        mutation PlayCard($cardId: Int!, $chosenSuit: String, $expectedTopCardId: Int, $turnNumber: Int!) {
+          playCard(cardId: $cardId, chosenSuit: $chosenSuit, expectedTopCardId: $expectedTopCardId, turnNumber: $turnNumber)
         }
       `,
-          { cardIndex, chosenSuit },
+          {
+            cardId: card.id,
+            chosenSuit,
+            expectedTopCardId: gameState?.topCard?.id ?? null,
+            turnNumber: gameState?.turnNumber ?? 0,
+          },
           playerNumber
         );
 
@@ -154,28 +169,36 @@ export function useWhotGame(playerNumber: 1 | 2) {
         throw err;
       }
     },
-    [playerNumber, fetchState]
+    [playerNumber, fetchState, gameState]
   );
 
   const drawCard = useCallback(async () => {
     try {
-      await mutateUserChain(`mutation { drawCard }`, {}, playerNumber);
+      await mutateUserChain(
+        `mutation DrawCard($turnNumber: Int!) { drawCard(turnNumber: $turnNumber) }`,
+        { turnNumber: gameState?.turnNumber ?? 0 },
+        playerNumber
+      );
       await fetchState();
     } catch (err) {
       console.error("[useWhotGame] drawCard error:", err);
       throw err;
     }
-  }, [playerNumber, fetchState]);
+  }, [playerNumber, fetchState, gameState]);
 
   const callLastCard = useCallback(async () => {
     try {
-      await mutateUserChain(`mutation { callLastCard }`, {}, playerNumber);
+      await mutateUserChain(
+        `mutation CallLastCard($lastCardSeq: Int!) { callLastCard(lastCardSeq: $lastCardSeq) }`,
+        { lastCardSeq: gameState?.lastCardSeq ?? 0 },
+        playerNumber
+      );
       await fetchState();
     } catch (err) {
       console.error("[useWhotGame] callLastCard error:", err);
       throw err;
     }
-  }, [playerNumber, fetchState]);
+  }, [playerNumber, fetchState, gameState]);
 
   // UPDATED: Inspo subscribe pattern - Player 1 subscribes, Player 2 auto-subscribes via joinMatch
   const joinGame = useCallback(
@@ -1,5 +1,6 @@
 // Game Types (matching backend schema)
 export interface Card {
+  id: number; // Stable per-match card id (PlayCard references it)
   suit: string;
   value: string;
 }
@@ -50,6 +51,8 @@ export interface PlayerView {
   activeShapeDemand: string | null;
   pendingPenalty: number;
   winnerIndex: number | null;
+  turnNumber: number; // Sent with every action so late/duplicate ones are rejected
+  lastCardSeq: number; // Sent with a last-card call so a repeated one is rejected
 }
 
 // Re-export Player as OpponentView for backward compatibility